@@ -23,7 +23,7 @@
 use rustc_middle::mir::{ConstraintCategory, ReturnConstraint};
 use rustc_middle::ty::GenericArgs;
 use rustc_middle::ty::TypeVisitor;
-use rustc_middle::ty::{self, RegionVid, Ty};
+use rustc_middle::ty::{self, GenericArgKind, RegionVid, Ty};
 use rustc_middle::ty::{Region, TyCtxt};
 use rustc_span::symbol::{kw, Ident};
 use rustc_span::Span;
@@ -37,6 +37,7 @@
 };
 
 use super::{OutlivesSuggestionBuilder, RegionName, RegionNameSource};
+use crate::constraints::OutlivesConstraint;
 use crate::region_infer::{BlameConstraint, ExtraConstraintInfo};
 use crate::{
     nll::ConstraintDescription,
@@ -447,7 +448,8 @@ pub(crate) fn report_region_error(
             self.regioncx.best_blame_constraint(fr, fr_origin, |r| {
                 self.regioncx.provides_universal_region(r, fr, outlived_fr)
             });
-        let BlameConstraint { category, cause, variance_info, .. } = blame_constraint;
+        let BlameConstraint { category, cause, variance_info, outlives_constraint, .. } =
+            blame_constraint;
 
         debug!("report_region_error: category={:?} {:?} {:?}", category, cause, variance_info);
 
@@ -553,6 +555,15 @@ pub(crate) fn report_region_error(
                 };
                 diag.note(format!("requirement occurs because of {desc}",));
                 diag.note(note);
+                // The outermost type is already part of `ty`, only point out
+                // the path if the requirement comes from further down.
+                let path = invariant_region_path(ty, param_index, outlives_constraint);
+                if path.len() > 1 {
+                    let path =
+                        path[1..].iter().rev().map(|ty| format!("`{ty}`")).collect::<Vec<_>>();
+                    let path = path.join(", which is nested in ");
+                    diag.note(format!("the lifetime appears in {path}"));
+                }
                 diag.help("see <https://doc.rust-lang.org/nomicon/subtyping.html> for more information about variance");
             }
         }
@@ -1226,3 +1237,60 @@ fn suggest_move_on_borrowing_closure(&self, diag: &mut Diag<'_>) {
         }
     }
 }
+
+/// Returns the types the region of `constraint` is nested in within the
+/// invariant generic argument described by `ty` and `param_index`, outermost
+/// first. This is only computed when reporting an error, so that relating
+/// types doesn't have to keep track of it.
+fn invariant_region_path<'tcx>(
+    ty: Ty<'tcx>,
+    param_index: u32,
+    constraint: OutlivesConstraint<'tcx>,
+) -> Vec<Ty<'tcx>> {
+    fn find_region<'tcx>(
+        ty: Ty<'tcx>,
+        is_target: &impl Fn(ty::Region<'tcx>) -> bool,
+        path: &mut Vec<Ty<'tcx>>,
+    ) -> bool {
+        match *ty.kind() {
+            ty::Ref(region, _, _) if is_target(region) => true,
+            ty::Ref(_, pointee, _) | ty::RawPtr(pointee, _) => {
+                path.push(ty);
+                let found = find_region(pointee, is_target, path);
+                if !found {
+                    path.pop();
+                }
+                found
+            }
+            ty::Adt(_, args) => {
+                path.push(ty);
+                let found = args.iter().any(|arg| match arg.unpack() {
+                    GenericArgKind::Lifetime(region) => is_target(region),
+                    GenericArgKind::Type(ty) => find_region(ty, is_target, path),
+                    GenericArgKind::Const(_) => false,
+                });
+                if !found {
+                    path.pop();
+                }
+                found
+            }
+            ty::Tuple(tys) => tys.iter().any(|ty| find_region(ty, is_target, path)),
+            ty::Array(ty, _) | ty::Slice(ty) => find_region(ty, is_target, path),
+            _ => false,
+        }
+    }
+
+    let arg = match *ty.kind() {
+        ty::RawPtr(inner, _) | ty::Ref(_, inner, _) => inner.into(),
+        ty::Adt(_, args) | ty::FnDef(_, args) => args[param_index as usize],
+        _ => return vec![],
+    };
+    let is_target = |region: ty::Region<'tcx>| {
+        matches!(region.kind(), ty::ReVar(vid) if vid == constraint.sup || vid == constraint.sub)
+    };
+    let mut path = vec![];
+    if let Some(ty) = arg.as_type() {
+        find_region(ty, &is_target, &mut path);
+    }
+    path
+}
@@ -5,8 +5,11 @@
 
 use rustc_infer::infer::TyCtxtInferExt;
 use rustc_middle::traits::ObligationCause;
+use rustc_middle::ty::error::TypeError;
 use rustc_middle::ty::{ParamEnv, Ty, TyCtxt, Variance};
-use rustc_trait_selection::traits::ObligationCtxt;
+use rustc_trait_selection::traits::{
+    FulfillmentErrorCode, MismatchedProjectionTypes, ObligationCtxt,
+};
 
 /// Returns whether the two types are equal up to subtyping.
 ///
@@ -40,19 +43,40 @@ pub fn relate_types<'tcx>(
     src: Ty<'tcx>,
     dest: Ty<'tcx>,
 ) -> bool {
+    try_relate_types(tcx, param_env, variance, src, dest).is_ok()
+}
+
+/// Like [`relate_types`], but returns why `src` is not a subtype of `dest`.
+///
+/// If relating the types only fails while proving the resulting obligations,
+/// the error of the first failing obligation is returned.
+pub fn try_relate_types<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    variance: Variance,
+    src: Ty<'tcx>,
+    dest: Ty<'tcx>,
+) -> Result<(), TypeError<'tcx>> {
     if src == dest {
-        return true;
+        return Ok(());
     }
 
     let mut builder = tcx.infer_ctxt().ignoring_regions();
     let infcx = builder.build();
-    let ocx = ObligationCtxt::new(&infcx);
+    let ocx = ObligationCtxt::new_with_diagnostics(&infcx);
     let cause = ObligationCause::dummy();
     let src = ocx.normalize(&cause, param_env, src);
     let dest = ocx.normalize(&cause, param_env, dest);
-    match ocx.relate(&cause, param_env, variance, src, dest) {
-        Ok(()) => {}
-        Err(_) => return false,
-    };
-    ocx.select_all_or_error().is_empty()
+    ocx.relate(&cause, param_env, variance, src, dest)?;
+    match ocx.select_all_or_error().into_iter().next() {
+        None => Ok(()),
+        Some(error) => Err(match error.code {
+            FulfillmentErrorCode::Subtype(_, err)
+            | FulfillmentErrorCode::ConstEquate(_, err)
+            | FulfillmentErrorCode::Project(MismatchedProjectionTypes { err }) => err,
+            FulfillmentErrorCode::Cycle(_)
+            | FulfillmentErrorCode::Select(_)
+            | FulfillmentErrorCode::Ambiguity { .. } => TypeError::Mismatch,
+        }),
+    }
 }
@@ -451,6 +451,14 @@ fn run_compiler(
                 sess.code_stats.print_type_sizes();
             }
 
+            if sess.opts.unstable_opts.print_relate_extremes {
+                sess.code_stats.print_relate_extremes();
+            }
+
+            if sess.opts.unstable_opts.relate_stats {
+                sess.code_stats.print_relate_stats();
+            }
+
             if sess.opts.unstable_opts.print_vtable_sizes {
                 let crate_name = queries.global_ctxt()?.enter(|tcx| tcx.crate_name(LOCAL_CRATE));
 
@@ -1424,7 +1432,7 @@ pub fn install_ice_hook(
     using_internal_features
 }
 
-/// Prints the ICE message, including query stack, but without backtrace.
+/// Prints the ICE message, including query stack and relate context, but without backtrace.
 ///
 /// The message will point the user at `bug_report_url` to report the ICE.
 ///
@@ -1508,7 +1516,9 @@ fn report_ice(
 
     let num_frames = if backtrace { None } else { Some(2) };
 
+    let relate_file = file.as_ref().and_then(|file| file.try_clone().ok());
     interface::try_print_query_stack(&dcx, num_frames, file);
+    interface::try_print_relate_context(&dcx, num_frames, relate_file);
 
     // We don't trust this callback not to panic itself, so run it at the end after we're sure we've
     // printed all the relevant info.
@@ -979,6 +979,10 @@ pub struct BuiltinAttribute {
         TEST, rustc_regions, Normal, template!(Word),
         WarnFollowing, EncodeCrossCrate::No
     ),
+    rustc_attr!(
+        TEST, rustc_relate_test, Normal, template!(List: "eq|sub|lub|glb|widen|diff|walk|matches|similarity"),
+        WarnFollowing, EncodeCrossCrate::No
+    ),
     rustc_attr!(
         TEST, rustc_error, Normal,
         template!(Word, List: "delayed_bug_from_inside_query"),
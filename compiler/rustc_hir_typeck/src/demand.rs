@@ -565,7 +565,7 @@ pub fn annotate_loop_expected_due_to_inference(
         expr: &hir::Expr<'_>,
         error: Option<TypeError<'tcx>>,
     ) {
-        let Some(TypeError::Sorts(ExpectedFound { expected, .. })) = error else {
+        let Some(ExpectedFound { expected, .. }) = error.and_then(TypeError::sorts) else {
             return;
         };
         let mut parent_id = self.tcx.parent_hir_id(expr.hir_id);
@@ -692,12 +692,24 @@ fn annotate_expected_due_to_let_ty(
             (hir::Node::LetStmt(hir::LetStmt { ty: Some(ty), init: Some(init), .. }), _)
                 if init.hir_id == expr.hir_id =>
             {
-                // Point at `let` assignment type.
-                err.span_label(ty.span, "expected due to this");
+                // Point at `let` assignment type, or at the element of a tuple type which
+                // didn't match.
+                let span = match (ty.kind, error) {
+                    (hir::TyKind::Tup(tys), Some(TypeError::TupleElementSorts(_, i)))
+                        if i < tys.len() =>
+                    {
+                        tys[i].span
+                    }
+                    _ => ty.span,
+                };
+                err.span_label(span, "expected due to this");
             }
             (
                 hir::Node::Expr(hir::Expr { kind: hir::ExprKind::Assign(lhs, rhs, _), .. }),
-                Some(TypeError::Sorts(ExpectedFound { expected, .. })),
+                Some(
+                    TypeError::Sorts(ExpectedFound { expected, .. })
+                    | TypeError::TupleElementSorts(ExpectedFound { expected, .. }, _),
+                ),
             ) if rhs.hir_id == expr.hir_id && !expected.is_closure() => {
                 // We ignore closures explicitly because we already point at them elsewhere.
                 // Point at the assigned-to binding.
@@ -781,7 +793,10 @@ fn annotate_expected_due_to_let_ty(
             }
             (
                 hir::Node::Expr(hir::Expr { kind: hir::ExprKind::Binary(_, lhs, rhs), .. }),
-                Some(TypeError::Sorts(ExpectedFound { expected, .. })),
+                Some(
+                    TypeError::Sorts(ExpectedFound { expected, .. })
+                    | TypeError::TupleElementSorts(ExpectedFound { expected, .. }, _),
+                ),
             ) if rhs.hir_id == expr.hir_id
                 && self.typeck_results.borrow().expr_ty_adjusted_opt(lhs) == Some(expected) =>
             {
@@ -797,7 +812,7 @@ fn annotate_alternative_method_deref(
         expr: &hir::Expr<'_>,
         error: Option<TypeError<'tcx>>,
     ) {
-        let Some(TypeError::Sorts(ExpectedFound { expected, .. })) = error else {
+        let Some(ExpectedFound { expected, .. }) = error.and_then(TypeError::sorts) else {
             return;
         };
         let hir::Node::Expr(hir::Expr { kind: hir::ExprKind::Assign(lhs, rhs, _), .. }) =
@@ -28,7 +28,7 @@
 use super::*;
 
 use rustc_middle::bug;
-use rustc_middle::ty::relate::{Relate, TypeRelation};
+use rustc_middle::ty::relate::{DumpRelation, Relate, TypeRelation};
 use rustc_middle::ty::{Const, ImplSubject};
 
 /// Whether we should define opaque types or just treat them opaquely.
@@ -119,10 +119,10 @@ pub fn sup<T>(
             self.param_env,
             define_opaque_types,
         );
-        fields
-            .sup()
-            .relate(expected, actual)
-            .map(|_| InferOk { value: (), obligations: fields.obligations })
+        ty::relate::with_relate_context("sup", &(expected, actual), Some(self.cause), || {
+            DumpRelation::new(fields.sup(), Some(ty::Contravariant)).relate(expected, actual)
+        })
+        .map(|_| InferOk { value: (), obligations: fields.obligations })
     }
 
     /// Makes `expected <: actual`.
@@ -141,10 +141,10 @@ pub fn sub<T>(
             self.param_env,
             define_opaque_types,
         );
-        fields
-            .sub()
-            .relate(expected, actual)
-            .map(|_| InferOk { value: (), obligations: fields.obligations })
+        ty::relate::with_relate_context("sub", &(expected, actual), Some(self.cause), || {
+            DumpRelation::new(fields.sub(), Some(ty::Covariant)).relate(expected, actual)
+        })
+        .map(|_| InferOk { value: (), obligations: fields.obligations })
     }
 
     /// Makes `expected == actual`.
@@ -163,10 +163,11 @@ pub fn eq<T>(
             self.param_env,
             define_opaque_types,
         );
-        fields
-            .equate(StructurallyRelateAliases::No)
-            .relate(expected, actual)
-            .map(|_| InferOk { value: (), obligations: fields.obligations })
+        ty::relate::with_relate_context("eq", &(expected, actual), Some(self.cause), || {
+            DumpRelation::new(fields.equate(StructurallyRelateAliases::No), Some(ty::Invariant))
+                .relate(expected, actual)
+        })
+        .map(|_| InferOk { value: (), obligations: fields.obligations })
     }
 
     /// Equates `expected` and `found` while structurally relating aliases.
@@ -187,10 +188,11 @@ pub fn eq_structurally_relating_aliases<T>(
             self.param_env,
             DefineOpaqueTypes::Yes,
         );
-        fields
-            .equate(StructurallyRelateAliases::Yes)
-            .relate(expected, actual)
-            .map(|_| InferOk { value: (), obligations: fields.obligations })
+        ty::relate::with_relate_context("eq", &(expected, actual), Some(self.cause), || {
+            DumpRelation::new(fields.equate(StructurallyRelateAliases::Yes), Some(ty::Invariant))
+                .relate(expected, actual)
+        })
+        .map(|_| InferOk { value: (), obligations: fields.obligations })
     }
 
     pub fn relate<T>(
@@ -237,10 +239,10 @@ pub fn lub<T>(
             self.param_env,
             define_opaque_types,
         );
-        fields
-            .lub()
-            .relate(expected, actual)
-            .map(|value| InferOk { value, obligations: fields.obligations })
+        ty::relate::with_relate_context("lub", &(expected, actual), Some(self.cause), || {
+            DumpRelation::new(fields.lub(), None).relate(expected, actual)
+        })
+        .map(|value| InferOk { value, obligations: fields.obligations })
     }
 
     /// Computes the greatest-lower-bound, or mutual subtype, of two
@@ -261,10 +263,10 @@ pub fn glb<T>(
             self.param_env,
             define_opaque_types,
         );
-        fields
-            .glb()
-            .relate(expected, actual)
-            .map(|value| InferOk { value, obligations: fields.obligations })
+        ty::relate::with_relate_context("glb", &(expected, actual), Some(self.cause), || {
+            DumpRelation::new(fields.glb(), None).relate(expected, actual)
+        })
+        .map(|value| InferOk { value, obligations: fields.obligations })
     }
 }
 
@@ -276,15 +278,19 @@ fn to_trace(
         b: Self,
     ) -> TypeTrace<'tcx> {
         match (a, b) {
-            (ImplSubject::Trait(trait_ref_a), ImplSubject::Trait(trait_ref_b)) => {
+            (ImplSubject::Trait(trait_ref_a, ..), ImplSubject::Trait(trait_ref_b, ..)) => {
                 ToTrace::to_trace(cause, a_is_expected, trait_ref_a, trait_ref_b)
             }
             (ImplSubject::Inherent(ty_a), ImplSubject::Inherent(ty_b)) => {
                 ToTrace::to_trace(cause, a_is_expected, ty_a, ty_b)
             }
-            (ImplSubject::Trait(_), ImplSubject::Inherent(_))
-            | (ImplSubject::Inherent(_), ImplSubject::Trait(_)) => {
-                bug!("can not trace TraitRef and Ty");
+            // Relating a trait impl to an inherent impl fails, so trace the
+            // types the impls are for, which are the closest thing to compare.
+            (ImplSubject::Trait(trait_ref_a, ..), ImplSubject::Inherent(ty_b)) => {
+                ToTrace::to_trace(cause, a_is_expected, trait_ref_a.self_ty(), ty_b)
+            }
+            (ImplSubject::Inherent(ty_a), ImplSubject::Trait(trait_ref_b, ..)) => {
+                ToTrace::to_trace(cause, a_is_expected, ty_a, trait_ref_b.self_ty())
             }
         }
     }
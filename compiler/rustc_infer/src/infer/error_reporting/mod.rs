@@ -72,7 +72,7 @@
 use rustc_middle::bug;
 use rustc_middle::dep_graph::DepContext;
 use rustc_middle::ty::print::{with_forced_trimmed_paths, PrintError, PrintTraitRefExt as _};
-use rustc_middle::ty::relate::{self, RelateResult, TypeRelation};
+use rustc_middle::ty::relate::{self, RelateCapabilities, RelateResult, TypeRelation};
 use rustc_middle::ty::Upcast;
 use rustc_middle::ty::{
     self, error::TypeError, IsSuggestable, List, Region, Ty, TyCtxt, TypeFoldable,
@@ -730,7 +730,7 @@ fn path_generic_args(
             }
         };
         match terr {
-            TypeError::Sorts(ref exp_found) => {
+            TypeError::Sorts(ref exp_found) | TypeError::TupleElementSorts(ref exp_found, _) => {
                 // if they are both "path types", there's a chance of ambiguity
                 // due to different versions of the same crate
                 if let (&ty::Adt(exp_adt, _), &ty::Adt(found_adt, _)) =
@@ -1718,6 +1718,11 @@ enum Mismatch<'a> {
             }
         };
 
+        // The label describing `terr` at `span` is added by `note_and_explain_type_err`,
+        // so that it's worded the same as for all other type mismatches.
+        let terr_is_label =
+            (prefer_label && is_simple_error) || &[span] == diag.span.primary_spans();
+        let mut terr_label_span = None;
         let mut label_or_note = |span: Span, msg: Cow<'static, str>| {
             if (prefer_label && is_simple_error) || &[span] == diag.span.primary_spans() {
                 diag.span_label(span, msg);
@@ -1745,17 +1750,23 @@ enum Mismatch<'a> {
         } else {
             if let Some(values) = values
                 && let Some((e, f)) = values.ty()
-                && let TypeError::ArgumentSorts(..) | TypeError::Sorts(_) = terr
+                && let TypeError::ArgumentSorts(..)
+                | TypeError::TupleElementSorts(..)
+                | TypeError::Sorts(_) = terr
             {
                 let e = self.tcx.erase_regions(e);
                 let f = self.tcx.erase_regions(f);
                 let expected = with_forced_trimmed_paths!(e.sort_string(self.tcx));
                 let found = with_forced_trimmed_paths!(f.sort_string(self.tcx));
-                if expected == found {
+                if expected == found && terr_is_label {
+                    terr_label_span = Some(span);
+                } else if expected == found {
                     label_or_note(span, terr.to_string(self.tcx));
                 } else {
                     label_or_note(span, Cow::from(format!("expected {expected}, found {found}")));
                 }
+            } else if terr_is_label {
+                terr_label_span = Some(span);
             } else {
                 label_or_note(span, terr.to_string(self.tcx));
             }
@@ -1817,7 +1828,9 @@ enum Similar<'tcx> {
 
             match terr {
                 // If two types mismatch but have similar names, mention that specifically.
-                TypeError::Sorts(values) if let Some(s) = similarity(values) => {
+                TypeError::Sorts(values) | TypeError::TupleElementSorts(values, _)
+                    if let Some(s) = similarity(values) =>
+                {
                     let diagnose_primitive =
                         |prim: Ty<'tcx>, shadow: Ty<'tcx>, defid: DefId, diag: &mut Diag<'_>| {
                             let name = shadow.sort_string(self.tcx);
@@ -1881,7 +1894,7 @@ enum Similar<'tcx> {
                         }
                     }
                 }
-                TypeError::Sorts(values) => {
+                TypeError::Sorts(values) | TypeError::TupleElementSorts(values, _) => {
                     let extra = expected == found;
                     let sort_string = |ty: Ty<'tcx>| match (extra, ty.kind()) {
                         (true, ty::Alias(ty::Opaque, ty::AliasTy { def_id, .. })) => {
@@ -1986,7 +1999,7 @@ enum Similar<'tcx> {
         };
         let exp_found = match terr {
             // `terr` has more accurate type information than `exp_found` in match expressions.
-            ty::error::TypeError::Sorts(terr)
+            ty::error::TypeError::Sorts(terr) | ty::error::TypeError::TupleElementSorts(terr, _)
                 if exp_found.is_some_and(|ef| terr.found == ef.found) =>
             {
                 Some(terr)
@@ -2020,12 +2033,13 @@ enum Similar<'tcx> {
 
         self.check_and_note_conflicting_crates(diag, terr);
 
-        self.note_and_explain_type_err(diag, terr, cause, span, cause.body_id.to_def_id());
+        let body_owner_def_id = cause.body_id.to_def_id();
+        self.note_and_explain_type_err(diag, terr, cause, span, body_owner_def_id, terr_label_span);
         if let Some(exp_found) = exp_found
             && let exp_found = TypeError::Sorts(exp_found)
             && exp_found != terr
         {
-            self.note_and_explain_type_err(diag, exp_found, cause, span, cause.body_id.to_def_id());
+            self.note_and_explain_type_err(diag, exp_found, cause, span, body_owner_def_id, None);
         }
 
         if let Some(ValuePairs::TraitRefs(exp_found)) = values
@@ -2703,6 +2717,10 @@ fn tag(&self) -> &'static str {
         "SameTypeModuloInfer"
     }
 
+    fn capabilities(&self) -> RelateCapabilities {
+        RelateCapabilities::default() | RelateCapabilities::INFER_MISMATCH
+    }
+
     fn relate_with_variance<T: relate::Relate<'tcx>>(
         &mut self,
         _variance: ty::Variance,
@@ -2727,7 +2745,7 @@ fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
             )
             | (ty::Infer(ty::InferTy::TyVar(_)), _)
             | (_, ty::Infer(ty::InferTy::TyVar(_))) => Ok(a),
-            (ty::Infer(_), _) | (_, ty::Infer(_)) => Err(TypeError::Mismatch),
+            // Other inference variables are a mismatch, see `capabilities`.
             _ => relate::structurally_relate_tys(self, a, b),
         }
     }
@@ -20,14 +20,18 @@ pub fn note_and_explain_type_err(
         cause: &ObligationCause<'tcx>,
         sp: Span,
         body_owner_def_id: DefId,
+        label_span: Option<Span>,
     ) {
         use ty::error::TypeError::*;
         debug!("note_and_explain_type_err err={:?} cause={:?}", err, cause);
 
         let tcx = self.tcx;
 
+        // The label goes wherever `note_type_err` decided to put it, if anywhere.
+        diag.subdiagnostic(tcx.dcx(), err.into_subdiagnostic(tcx, label_span));
+
         match err {
-            ArgumentSorts(values, _) | Sorts(values) => {
+            ArgumentSorts(values, _) | TupleElementSorts(values, _) | Sorts(values) => {
                 match (*values.expected.kind(), *values.found.kind()) {
                     (ty::Closure(..), ty::Closure(..)) => {
                         diag.note("no two closures, even if identical, have the same type");
@@ -483,22 +487,17 @@ fn foo(&self, x: T) -> T { x }
                     values.found.kind(),
                 );
             }
-            CyclicTy(ty) => {
-                // Watch out for various cases of cyclic types and try to explain.
-                if ty.is_closure() || ty.is_coroutine() || ty.is_coroutine_closure() {
-                    diag.note(
-                        "closures cannot capture themselves or take themselves as argument;\n\
-                         this error may be the result of a recent compiler bug-fix,\n\
-                         see issue #46062 <https://github.com/rust-lang/rust/issues/46062>\n\
-                         for more information",
-                    );
-                }
+            InlineConstParentArgMismatch(_, param_def_id) => {
+                diag.span_note(
+                    tcx.def_span(param_def_id),
+                    format!(
+                        "the inline constant uses the generic parameter `{}` of its enclosing item",
+                        tcx.item_name(param_def_id)
+                    ),
+                );
             }
             TargetFeatureCast(def_id) => {
                 let target_spans = tcx.get_attrs(def_id, sym::target_feature).map(|attr| attr.span);
-                diag.note(
-                    "functions with `#[target_feature]` can only be coerced to `unsafe` function pointers"
-                );
                 diag.span_labels(target_spans, "`#[target_feature]` added here");
             }
             _ => {}
@@ -9,7 +9,7 @@
 use rustc_middle::bug;
 use rustc_middle::infer::unify_key::ConstVariableValue;
 use rustc_middle::ty::error::TypeError;
-use rustc_middle::ty::relate::{self, Relate, RelateResult, TypeRelation};
+use rustc_middle::ty::relate::{self, Relate, RelateCapabilities, RelateResult, TypeRelation};
 use rustc_middle::ty::visit::MaxUniverse;
 use rustc_middle::ty::{self, Ty, TyCtxt};
 use rustc_middle::ty::{AliasRelationDirection, InferConst, Term, TypeVisitable, TypeVisitableExt};
@@ -404,6 +404,22 @@ fn tag(&self) -> &'static str {
         "Generalizer"
     }
 
+    fn capabilities(&self) -> RelateCapabilities {
+        // `VarianceDiagInfo` is ignored by this relation, so don't bother
+        // computing it.
+        RelateCapabilities::REGION_SENSITIVE | RelateCapabilities::CONST_EVAL
+    }
+
+    fn structurally_relate_aliases(&self) -> StructurallyRelateAliases {
+        self.structurally_relate_aliases
+    }
+
+    fn can_shortcircuit_on_equality(&self) -> bool {
+        // All regions have to be replaced with new region variables, even if
+        // they don't contain any inference variables.
+        false
+    }
+
     fn relate_item_args(
         &mut self,
         item_def_id: DefId,
@@ -418,14 +434,7 @@ fn relate_item_args(
         } else {
             let tcx = self.tcx();
             let opt_variances = tcx.variances_of(item_def_id);
-            relate::relate_args_with_variances(
-                self,
-                item_def_id,
-                opt_variances,
-                a_arg,
-                b_arg,
-                false,
-            )
+            relate::relate_args_with_variances(self, item_def_id, opt_variances, a_arg, b_arg)
         }
     }
 
@@ -30,6 +30,16 @@ fn tcx(&self) -> TyCtxt<'tcx> {
         self.fields.tcx()
     }
 
+    fn validation_probe<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> Option<T> {
+        // Obligations aren't part of snapshots, so drop the ones registered
+        // while probing by hand.
+        let infcx = self.fields.infcx;
+        let obligations_len = self.fields.obligations.len();
+        let r = infcx.probe(|_| f(self));
+        self.fields.obligations.truncate(obligations_len);
+        Some(r)
+    }
+
     fn relate_with_variance<T: Relate<'tcx>>(
         &mut self,
         variance: ty::Variance,
@@ -132,10 +142,6 @@ fn span(&self) -> Span {
         self.fields.trace.span()
     }
 
-    fn structurally_relate_aliases(&self) -> StructurallyRelateAliases {
-        StructurallyRelateAliases::No
-    }
-
     fn param_env(&self) -> ty::ParamEnv<'tcx> {
         self.fields.param_env
     }
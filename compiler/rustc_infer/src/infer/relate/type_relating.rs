@@ -6,7 +6,8 @@
 use crate::traits::{Obligation, PredicateObligations};
 
 use rustc_middle::ty::relate::{
-    relate_args_invariantly, relate_args_with_variances, Relate, RelateResult, TypeRelation,
+    relate_args_invariantly, relate_args_with_variances, Relate, RelateCapabilities, RelateResult,
+    TypeRelation,
 };
 use rustc_middle::ty::TyVar;
 use rustc_middle::ty::{self, Ty, TyCtxt};
@@ -29,56 +30,9 @@ pub fn new(
     }
 }
 
-impl<'tcx> TypeRelation<'tcx> for TypeRelating<'_, '_, 'tcx> {
-    fn tag(&self) -> &'static str {
-        "TypeRelating"
-    }
-
-    fn tcx(&self) -> TyCtxt<'tcx> {
-        self.fields.infcx.tcx
-    }
-
-    fn relate_item_args(
-        &mut self,
-        item_def_id: rustc_hir::def_id::DefId,
-        a_arg: ty::GenericArgsRef<'tcx>,
-        b_arg: ty::GenericArgsRef<'tcx>,
-    ) -> RelateResult<'tcx, ty::GenericArgsRef<'tcx>> {
-        if self.ambient_variance == ty::Variance::Invariant {
-            // Avoid fetching the variance if we are in an invariant
-            // context; no need, and it can induce dependency cycles
-            // (e.g., #41849).
-            relate_args_invariantly(self, a_arg, b_arg)
-        } else {
-            let tcx = self.tcx();
-            let opt_variances = tcx.variances_of(item_def_id);
-            relate_args_with_variances(self, item_def_id, opt_variances, a_arg, b_arg, false)
-        }
-    }
-
-    fn relate_with_variance<T: Relate<'tcx>>(
-        &mut self,
-        variance: ty::Variance,
-        _info: ty::VarianceDiagInfo<'tcx>,
-        a: T,
-        b: T,
-    ) -> RelateResult<'tcx, T> {
-        let old_ambient_variance = self.ambient_variance;
-        self.ambient_variance = self.ambient_variance.xform(variance);
-        debug!(?self.ambient_variance, "new ambient variance");
-
-        let r = if self.ambient_variance == ty::Bivariant { Ok(a) } else { self.relate(a, b) };
-
-        self.ambient_variance = old_ambient_variance;
-        r
-    }
-
-    #[instrument(skip(self), level = "debug")]
-    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
-        if a == b {
-            return Ok(a);
-        }
-
+impl<'tcx> TypeRelating<'_, '_, 'tcx> {
+    /// The implementation of `TypeRelation::tys`, for types which are not trivially equal.
+    fn relate_tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
         let infcx = self.fields.infcx;
         let a = infcx.shallow_resolve(a);
         let b = infcx.shallow_resolve(b);
@@ -168,6 +122,92 @@ fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
 
         Ok(a)
     }
+}
+
+impl<'tcx> TypeRelation<'tcx> for TypeRelating<'_, '_, 'tcx> {
+    fn tag(&self) -> &'static str {
+        "TypeRelating"
+    }
+
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.fields.infcx.tcx
+    }
+
+    fn capabilities(&self) -> RelateCapabilities {
+        // `VarianceDiagInfo` is ignored by this relation, so don't bother
+        // computing it.
+        RelateCapabilities::REGION_SENSITIVE | RelateCapabilities::CONST_EVAL
+    }
+
+    fn structurally_relate_aliases(&self) -> StructurallyRelateAliases {
+        self.structurally_relate_aliases
+    }
+
+    fn normalize_const_exprs(&self) -> bool {
+        true
+    }
+
+    fn validation_probe<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> Option<T> {
+        // Obligations aren't part of snapshots, so drop the ones registered
+        // while probing by hand.
+        let infcx = self.fields.infcx;
+        let obligations_len = self.fields.obligations.len();
+        let r = infcx.probe(|_| f(self));
+        self.fields.obligations.truncate(obligations_len);
+        Some(r)
+    }
+
+    fn relates_for_equality(&self) -> bool {
+        self.ambient_variance == ty::Invariant
+    }
+
+    fn relate_item_args(
+        &mut self,
+        item_def_id: rustc_hir::def_id::DefId,
+        a_arg: ty::GenericArgsRef<'tcx>,
+        b_arg: ty::GenericArgsRef<'tcx>,
+    ) -> RelateResult<'tcx, ty::GenericArgsRef<'tcx>> {
+        if self.ambient_variance == ty::Variance::Invariant {
+            // Avoid fetching the variance if we are in an invariant
+            // context; no need, and it can induce dependency cycles
+            // (e.g., #41849).
+            relate_args_invariantly(self, a_arg, b_arg)
+        } else {
+            let tcx = self.tcx();
+            let opt_variances = tcx.variances_of(item_def_id);
+            relate_args_with_variances(self, item_def_id, opt_variances, a_arg, b_arg)
+        }
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        variance: ty::Variance,
+        _info: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        let old_ambient_variance = self.ambient_variance;
+        self.ambient_variance = self.ambient_variance.xform(variance);
+        debug!(?self.ambient_variance, "new ambient variance");
+
+        let r = if self.ambient_variance == ty::Bivariant { Ok(a) } else { self.relate(a, b) };
+
+        self.ambient_variance = old_ambient_variance;
+        r
+    }
+
+    #[instrument(skip(self), level = "debug")]
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        if a == b {
+            return Ok(a);
+        }
+
+        let result = self.relate_tys(a, b);
+        if self.ambient_variance == ty::Invariant {
+            ty::relate::debug_check_stable_hash_consistency(self.tcx(), a, b, &result);
+        }
+        result
+    }
 
     fn regions(
         &mut self,
@@ -308,10 +348,6 @@ fn param_env(&self) -> ty::ParamEnv<'tcx> {
         self.fields.param_env
     }
 
-    fn structurally_relate_aliases(&self) -> StructurallyRelateAliases {
-        self.structurally_relate_aliases
-    }
-
     fn register_predicates(
         &mut self,
         obligations: impl IntoIterator<Item: ty::Upcast<TyCtxt<'tcx>, ty::Predicate<'tcx>>>,
@@ -558,3 +558,30 @@ pub fn try_print_query_stack(
         eprintln!("we're just showing a limited slice of the query stack");
     }
 }
+
+pub fn try_print_relate_context(
+    dcx: &DiagCtxt,
+    num_frames: Option<usize>,
+    file: Option<std::fs::File>,
+) {
+    if !ty::relate::has_relate_context() {
+        return;
+    }
+
+    // Printing the related values needs a `TyCtxt`, so only print the relate
+    // context, including its header and footer, if one is available.
+    ty::tls::with_context_opt(|icx| {
+        if icx.is_none() {
+            return;
+        }
+
+        eprintln!("relate context during panic:");
+        let i =
+            ty::print::with_no_queries!(ty::relate::print_relate_context(dcx, num_frames, file));
+        if num_frames == None || num_frames >= Some(i) {
+            eprintln!("end of relate context");
+        } else {
+            eprintln!("we're just showing a limited slice of the relate context");
+        }
+    })
+}
@@ -18,6 +18,7 @@
 mod hash_stable;
 mod lift;
 mod query;
+mod relate;
 mod serialize;
 mod symbols;
 mod type_foldable;
@@ -102,6 +103,17 @@ pub fn extension(attr: TokenStream, input: TokenStream) -> TokenStream {
     /// visited (and its type is not required to implement `TypeVisitable`).
     type_visitable::type_visitable_derive
 );
+decl_derive!(
+    [Relate, attributes(relate)] =>
+    /// Derives `Relate` for the annotated `struct` or `enum` (`union` is not supported).
+    ///
+    /// Each field of the struct or enum variant will be related with the corresponding field of
+    /// the other value in definition order, using the `Relate` implementation for its type. Two
+    /// values of different enum variants fail to relate with `TypeError::Mismatch`, unless the
+    /// enum is annotated with `#[relate(mismatch = Variant)]`, in which case they fail with
+    /// `TypeError::Variant` of both values.
+    relate::relate_derive
+);
 decl_derive!([Lift, attributes(lift)] => lift::lift_derive);
 decl_derive!(
     [Diagnostic, attributes(
@@ -0,0 +1,80 @@
+use quote::{format_ident, quote};
+use syn::parse_quote;
+
+pub fn relate_derive(mut s: synstructure::Structure<'_>) -> proc_macro2::TokenStream {
+    if let syn::Data::Union(_) = s.ast().data {
+        panic!("cannot derive on union")
+    }
+
+    s.underscore_const(true);
+
+    if !s.ast().generics.lifetimes().any(|lt| lt.lifetime.ident == "tcx") {
+        s.add_impl_generic(parse_quote! { 'tcx });
+    }
+
+    s.add_bounds(synstructure::AddBounds::Generics);
+    s.bind_with(|_| synstructure::BindStyle::Move);
+
+    // The `TypeError` variant to return if the values are of different enum
+    // variants, from `#[relate(mismatch = Variant)]`.
+    let mut mismatch_error = None;
+    s.ast().attrs.iter().for_each(|x| {
+        if !x.path().is_ident("relate") {
+            return;
+        }
+        let _ = x.parse_nested_meta(|nested| {
+            if nested.path.is_ident("mismatch") {
+                mismatch_error = Some(nested.value()?.parse::<syn::Ident>()?);
+            }
+            Ok(())
+        });
+    });
+
+    // The fields of `a` and `b` are bound to `__a_{index}` and `__b_{index}`
+    // respectively, so that both can be matched on at the same time.
+    let mut a = s.clone();
+    a.binding_name(|_, index| format_ident!("__a_{}", index));
+    let mut b = s.clone();
+    b.binding_name(|_, index| format_ident!("__b_{}", index));
+
+    let arms = std::iter::zip(a.variants(), b.variants()).map(|(a_variant, b_variant)| {
+        let (a_pat, b_pat) = (a_variant.pat(), b_variant.pat());
+        let (a_bindings, b_bindings) = (a_variant.bindings(), b_variant.bindings());
+        let related = a_variant.construct(|_, index| {
+            let (a_bi, b_bi) = (&a_bindings[index], &b_bindings[index]);
+            quote! {
+                ::rustc_middle::ty::relate::TypeRelation::relate(__relation, #a_bi, #b_bi)?
+            }
+        });
+        quote! { (#a_pat, #b_pat) => #related, }
+    });
+    let mismatch = if s.variants().len() > 1 {
+        let error = match mismatch_error {
+            Some(variant) => quote! {
+                ::rustc_middle::ty::error::TypeError::#variant(
+                    ::rustc_middle::ty::relate::expected_found(__a, __b)
+                )
+            },
+            None => quote! { ::rustc_middle::ty::error::TypeError::Mismatch },
+        };
+        quote! { _ => return Err(#error), }
+    } else {
+        quote! {}
+    };
+
+    s.bound_impl(
+        quote!(::rustc_middle::ty::relate::Relate<'tcx>),
+        quote! {
+            fn relate<__R: ::rustc_middle::ty::relate::TypeRelation<'tcx>>(
+                __relation: &mut __R,
+                __a: Self,
+                __b: Self,
+            ) -> ::rustc_middle::ty::relate::RelateResult<'tcx, Self> {
+                Ok(match (__a, __b) {
+                    #(#arms)*
+                    #mismatch
+                })
+            }
+        },
+    )
+}
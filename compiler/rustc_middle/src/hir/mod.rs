@@ -123,7 +123,9 @@ pub fn parent_module_from_def_id(self, mut id: LocalDefId) -> LocalModDefId {
 
     pub fn impl_subject(self, def_id: DefId) -> EarlyBinder<'tcx, ImplSubject<'tcx>> {
         match self.impl_trait_ref(def_id) {
-            Some(t) => t.map_bound(ImplSubject::Trait),
+            Some(t) => t.map_bound(|trait_ref| {
+                ImplSubject::Trait(trait_ref, self.impl_polarity(def_id), self.constness(def_id))
+            }),
             None => self.type_of(def_id).map_bound(ImplSubject::Inherent),
         }
     }
@@ -1,7 +1,8 @@
 //! Registering limits:
 //! * recursion_limit,
-//! * move_size_limit, and
-//! * type_length_limit
+//! * move_size_limit,
+//! * type_length_limit, and
+//! * relate_depth_limit
 //!
 //! There are various parts of the compiler that must impose arbitrary limits
 //! on how deeply they recurse to prevent stack overflow. Users can override
@@ -18,20 +19,24 @@
 use std::num::IntErrorKind;
 
 pub fn provide(providers: &mut Providers) {
-    providers.limits = |tcx, ()| Limits {
-        recursion_limit: get_recursion_limit(tcx.hir().krate_attrs(), tcx.sess),
-        move_size_limit: get_limit(
-            tcx.hir().krate_attrs(),
-            tcx.sess,
-            sym::move_size_limit,
-            tcx.sess.opts.unstable_opts.move_size_limit.unwrap_or(0),
-        ),
-        type_length_limit: get_limit(
-            tcx.hir().krate_attrs(),
-            tcx.sess,
-            sym::type_length_limit,
-            1048576,
-        ),
+    providers.limits = |tcx, ()| {
+        let recursion_limit = get_recursion_limit(tcx.hir().krate_attrs(), tcx.sess);
+        Limits {
+            recursion_limit,
+            move_size_limit: get_limit(
+                tcx.hir().krate_attrs(),
+                tcx.sess,
+                sym::move_size_limit,
+                tcx.sess.opts.unstable_opts.move_size_limit.unwrap_or(0),
+            ),
+            type_length_limit: get_limit(
+                tcx.hir().krate_attrs(),
+                tcx.sess,
+                sym::type_length_limit,
+                1048576,
+            ),
+            relate_depth_limit: get_relate_depth_limit(tcx.sess, recursion_limit),
+        }
     }
 }
 
@@ -39,6 +44,16 @@ pub fn get_recursion_limit(krate_attrs: &[Attribute], sess: &Session) -> Limit {
     get_limit(krate_attrs, sess, sym::recursion_limit, 128)
 }
 
+/// The relate depth limit is derived from the recursion limit, so that raising
+/// `#![recursion_limit]` also allows relating more deeply nested types. It can be
+/// set directly with `-Zrelate-depth-limit`.
+fn get_relate_depth_limit(sess: &Session, recursion_limit: Limit) -> Limit {
+    match sess.opts.unstable_opts.relate_depth_limit {
+        Some(limit) => Limit::new(limit),
+        None => recursion_limit * 16,
+    }
+}
+
 fn get_limit(krate_attrs: &[Attribute], sess: &Session, name: Symbol, default: usize) -> Limit {
     match get_limit_size(krate_attrs, sess, name) {
         Some(size) => Limit::new(size),
@@ -1989,6 +1989,17 @@
         desc { "normalizing `{}`", goal.value }
     }
 
+    /// Returns whether the two types are structurally equal after erasing
+    /// their regions and normalizing them in the param env, without an
+    /// inference context. Error types are equal to every type.
+    ///
+    /// The types must not contain inference variables.
+    query structurally_equal_modulo_regions(
+        key: ParamEnvAnd<'tcx, (Ty<'tcx>, Ty<'tcx>)>
+    ) -> bool {
+        desc { "checking whether `{}` and `{}` are structurally equal", key.value.0, key.value.1 }
+    }
+
     query implied_outlives_bounds_compat(
         goal: CanonicalTyGoal<'tcx>
     ) -> Result<
@@ -78,7 +78,9 @@
 use std::borrow::Borrow;
 use std::cmp::Ordering;
 use std::fmt;
+use std::fs::File;
 use std::hash::{Hash, Hasher};
+use std::io::{BufWriter, Write as _};
 use std::iter;
 use std::marker::PhantomData;
 use std::mem;
@@ -851,6 +853,10 @@ pub struct GlobalCtxt<'tcx> {
     /// Stores memory for globals (statics/consts).
     pub(crate) alloc_map: Lock<interpret::AllocMap<'tcx>>,
 
+    /// The file type relations are dumped to with `-Zdump-type-relations`,
+    /// created when the first relation is dumped and flushed by `finish`.
+    pub(crate) type_relations_dump: Lock<Option<BufWriter<File>>>,
+
     current_gcx: CurrentGcx,
 }
 
@@ -879,7 +885,14 @@ pub fn enter<F, R>(&'tcx self, f: F) -> R
     }
 
     pub fn finish(&self) -> FileEncodeResult {
-        self.dep_graph.finish_encoding()
+        let result = self.dep_graph.finish_encoding();
+        if let Some(dump) = self.type_relations_dump.lock().as_mut()
+            && let Err(err) = dump.flush()
+        {
+            let path = self.sess.opts.unstable_opts.dump_type_relations.clone().unwrap();
+            return Err((path, err));
+        }
+        result
     }
 }
 
@@ -1080,6 +1093,7 @@ pub fn create_global_ctxt(
             canonical_param_env_cache: Default::default(),
             data_layout,
             alloc_map: Lock::new(interpret::AllocMap::new()),
+            type_relations_dump: Default::default(),
             current_gcx,
         }
     }
@@ -1614,6 +1628,10 @@ pub fn move_size_limit(self) -> Limit {
         self.limits(()).move_size_limit
     }
 
+    pub fn relate_depth_limit(self) -> Limit {
+        self.limits(()).relate_depth_limit
+    }
+
     pub fn all_traits(self) -> impl Iterator<Item = DefId> + 'tcx {
         iter::once(LOCAL_CRATE)
             .chain(self.used_crates(()).iter().copied())
@@ -2298,6 +2316,14 @@ pub fn mk_place_elem(self, place: Place<'tcx>, elem: PlaceElem<'tcx>) -> Place<'
         Place { local: place.local, projection: self.mk_place_elems(&projection) }
     }
 
+    /// Interns a list of existential predicates, which must be sorted by
+    /// [`stable_cmp`].
+    ///
+    /// Duplicate predicates are removed, so that interned lists are always in
+    /// canonical form and can be compared element-wise, e.g. when relating
+    /// trait objects.
+    ///
+    /// [`stable_cmp`]: ty::ExistentialPredicateStableCmpExt::stable_cmp
     pub fn mk_poly_existential_predicates(
         self,
         eps: &[PolyExistentialPredicate<'tcx>],
@@ -2308,6 +2334,11 @@ pub fn mk_poly_existential_predicates(
                 .all(|[a, b]| a.skip_binder().stable_cmp(self, &b.skip_binder())
                     != Ordering::Greater)
         );
+        if eps.array_windows().any(|[a, b]| a == b) {
+            let mut eps = eps.to_vec();
+            eps.dedup();
+            return self.intern_poly_existential_predicates(&eps);
+        }
         self.intern_poly_existential_predicates(eps)
     }
 
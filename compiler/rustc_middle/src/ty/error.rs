@@ -1,11 +1,12 @@
 use crate::ty::print::{with_forced_trimmed_paths, FmtPrinter, PrettyPrinter};
 use crate::ty::{self, BoundRegionKind, Region, Ty, TyCtxt};
-use rustc_errors::pluralize;
+use rustc_errors::{pluralize, Diag, EmissionGuarantee, SubdiagMessageOp, Subdiagnostic};
 use rustc_hir as hir;
 use rustc_hir::def::{CtorOf, DefKind};
 use rustc_hir::def_id::DefId;
 use rustc_macros::{TypeFoldable, TypeVisitable};
 use rustc_span::symbol::Symbol;
+use rustc_span::Span;
 use rustc_target::spec::abi;
 use std::borrow::Cow;
 use std::hash::{DefaultHasher, Hash, Hasher};
@@ -40,6 +41,10 @@ pub enum TypeError<'tcx> {
     ArgumentMutability(usize),
     TupleSize(ExpectedFound<usize>),
     FixedArraySize(ExpectedFound<u64>),
+    /// Two uses of a `#[repr(simd)]` type have a different number of lanes.
+    SimdLaneCount(ExpectedFound<u64>),
+    /// Two uses of a `#[repr(simd)]` type have a different element type.
+    SimdElement(ExpectedFound<Ty<'tcx>>),
     ArgCount,
     FieldMisMatch(Symbol, Symbol),
 
@@ -49,6 +54,8 @@ pub enum TypeError<'tcx> {
 
     Sorts(ExpectedFound<Ty<'tcx>>),
     ArgumentSorts(ExpectedFound<Ty<'tcx>>, usize),
+    /// The types of the `usize`th elements of two tuples don't match.
+    TupleElementSorts(ExpectedFound<Ty<'tcx>>, usize),
     Traits(ExpectedFound<DefId>),
     VariadicMismatch(ExpectedFound<bool>),
 
@@ -59,11 +66,40 @@ pub enum TypeError<'tcx> {
     CyclicConst(ty::Const<'tcx>),
     ProjectionMismatched(ExpectedFound<DefId>),
     ExistentialMismatch(ExpectedFound<&'tcx ty::List<ty::PolyExistentialPredicate<'tcx>>>),
+    /// Two trait objects differ in their auto traits. Each side which is
+    /// `Some` names an auto trait which is missing from the other side.
+    MissingAutoTrait(ExpectedFound<Option<DefId>>),
     ConstMismatch(ExpectedFound<ty::Const<'tcx>>),
+    /// Two constant values differ in the element or field at the given path,
+    /// e.g. `[1].x`, whose values are the two constants.
+    ConstValueMismatch(ExpectedFound<ty::Const<'tcx>>, Symbol),
+    /// The patterns of two pattern types don't include the same values.
+    PatternMismatch(ExpectedFound<ty::Pattern<'tcx>>),
+    /// The arguments of two inline consts differ in an argument inherited from
+    /// the enclosing item, whose generic parameter is given by the `DefId`.
+    InlineConstParentArgMismatch(ExpectedFound<ty::GenericArg<'tcx>>, DefId),
+    /// One of two impls is a trait impl and the other one an inherent impl.
+    ImplSubjectMismatch(ExpectedFound<ty::ImplSubject<'tcx>>),
+    /// Two trait impls have a different polarity, e.g. `impl Send for Foo`
+    /// and `impl !Send for Foo`.
+    ImplPolarityMismatch(ExpectedFound<ty::ImplPolarity>),
+    /// Two trait impls differ in whether they are `const`.
+    ImplConstnessMismatch(ExpectedFound<hir::Constness>),
+    /// Two predicates are of a different kind, e.g. a trait predicate and an
+    /// outlives predicate.
+    PredicateKindMismatch(ExpectedFound<ty::PredicateKind<'tcx>>),
+    /// Two terms are of a different kind, i.e. a type and a constant.
+    TermKindMismatch(ExpectedFound<ty::Term<'tcx>>),
+    /// Two binders bind a different number or different kinds of variables,
+    /// which are anonymized and listed in the order of their first use.
+    BoundVarMismatch(ExpectedFound<&'tcx ty::List<ty::BoundVariableKind>>),
 
     IntrinsicCast,
     /// Safe `#[target_feature]` functions are not assignable to safe function pointers.
     TargetFeatureCast(DefId),
+    /// The types are nested more deeply than the relate depth limit, which is
+    /// carried by this variant.
+    RelateDepthLimitReached(usize),
 }
 
 impl TypeError<'_> {
@@ -128,6 +164,19 @@ fn report_maybe_different(expected: &str, found: &str) -> String {
                 pluralize!(values.found)
             )
             .into(),
+            SimdLaneCount(values) => format!(
+                "expected a SIMD vector with {} lane{}, found one with {} lane{}",
+                values.expected,
+                pluralize!(values.expected),
+                values.found,
+                pluralize!(values.found)
+            )
+            .into(),
+            SimdElement(values) => format!(
+                "expected a SIMD vector of `{}`, found one of `{}`",
+                values.expected, values.found
+            )
+            .into(),
             ArgCount => "incorrect number of function parameters".into(),
             FieldMisMatch(adt, field) => format!("field type mismatch: {adt}.{field}").into(),
             RegionsDoesNotOutlive(..) => "lifetime mismatch".into(),
@@ -136,7 +185,7 @@ fn report_maybe_different(expected: &str, found: &str) -> String {
                 "one type is more general than the other".into()
             }
             RegionsPlaceholderMismatch => "one type is more general than the other".into(),
-            ArgumentSorts(values, _) | Sorts(values) => {
+            ArgumentSorts(values, _) | TupleElementSorts(values, _) | Sorts(values) => {
                 let expected = values.expected.sort_string(tcx);
                 let found = values.found.sort_string(tcx);
                 report_maybe_different(&expected, &found).into()
@@ -170,28 +219,153 @@ fn report_maybe_different(expected: &str, found: &str) -> String {
                 &format!("trait `{}`", values.found),
             )
             .into(),
+            MissingAutoTrait(values) => match (values.expected, values.found) {
+                (Some(expected), Some(found)) => format!(
+                    "expected a trait object with the auto trait `{}`, \
+                    found one with the auto trait `{}`",
+                    tcx.def_path_str(expected),
+                    tcx.def_path_str(found)
+                )
+                .into(),
+                (Some(expected), None) => format!(
+                    "expected a trait object with the auto trait `{}`, found one without it",
+                    tcx.def_path_str(expected)
+                )
+                .into(),
+                (None, Some(found)) => format!(
+                    "expected a trait object without the auto trait `{}`, found one with it",
+                    tcx.def_path_str(found)
+                )
+                .into(),
+                (None, None) => "expected a trait object with different auto traits".into(),
+            },
             ConstMismatch(ref values) => {
                 format!("expected `{}`, found `{}`", values.expected, values.found).into()
             }
+            ConstValueMismatch(ref values, path) => {
+                format!("expected `{}`, found `{}` at `{path}`", values.expected, values.found)
+                    .into()
+            }
+            PatternMismatch(ref values) => format!(
+                "expected a pattern type of `{:?}`, found one of `{:?}`",
+                values.expected, values.found
+            )
+            .into(),
+            InlineConstParentArgMismatch(ref values, param_def_id) => format!(
+                "expected `{}`, found `{}` for the generic parameter `{}` of the enclosing item",
+                values.expected,
+                values.found,
+                tcx.item_name(param_def_id)
+            )
+            .into(),
+            ImplSubjectMismatch(values) => {
+                let describe = |subject| match subject {
+                    ty::ImplSubject::Trait(..) => "a trait impl",
+                    ty::ImplSubject::Inherent(_) => "an inherent impl",
+                };
+                format!("expected {}, found {}", describe(values.expected), describe(values.found))
+                    .into()
+            }
+            ImplPolarityMismatch(values) => {
+                format!("expected a {} impl, found a {} impl", values.expected, values.found).into()
+            }
+            ImplConstnessMismatch(values) => {
+                format!("expected a {} impl, found a {} impl", values.expected, values.found).into()
+            }
+            PredicateKindMismatch(values) => format!(
+                "expected {}, found {}",
+                predicate_kind_descr(values.expected),
+                predicate_kind_descr(values.found)
+            )
+            .into(),
+            TermKindMismatch(values) => {
+                let describe = |term: ty::Term<'_>| match term.unpack() {
+                    ty::TermKind::Ty(_) => "a type",
+                    ty::TermKind::Const(_) => "a constant",
+                };
+                format!("expected {}, found {}", describe(values.expected), describe(values.found))
+                    .into()
+            }
+            BoundVarMismatch(values) => format!(
+                "expected a binder with {}, found one with {}",
+                bound_vars_descr(values.expected),
+                bound_vars_descr(values.found)
+            )
+            .into(),
             IntrinsicCast => "cannot coerce intrinsics to function pointers".into(),
             TargetFeatureCast(_) => {
                 "cannot coerce functions with `#[target_feature]` to safe function pointers".into()
             }
+            RelateDepthLimitReached(limit) => {
+                format!("reached the depth limit of {limit} while comparing nested types").into()
+            }
         }
     }
 }
 
+/// Describes the kind of `predicate`, e.g. "a trait predicate".
+fn predicate_kind_descr(predicate: ty::PredicateKind<'_>) -> &'static str {
+    match predicate {
+        ty::PredicateKind::Clause(clause) => match clause {
+            ty::ClauseKind::Trait(_) => "a trait predicate",
+            ty::ClauseKind::RegionOutlives(_) => "a region outlives predicate",
+            ty::ClauseKind::TypeOutlives(_) => "a type outlives predicate",
+            ty::ClauseKind::Projection(_) => "a projection predicate",
+            ty::ClauseKind::ConstArgHasType(..) => "a const argument type predicate",
+            ty::ClauseKind::WellFormed(_) => "a well-formedness predicate",
+            ty::ClauseKind::ConstEvaluatable(_) => "a const-evaluatable predicate",
+        },
+        ty::PredicateKind::ObjectSafe(_) => "an object safety predicate",
+        ty::PredicateKind::Subtype(_) => "a subtype predicate",
+        ty::PredicateKind::Coerce(_) => "a coercion predicate",
+        ty::PredicateKind::ConstEquate(..) => "a const equality predicate",
+        ty::PredicateKind::Ambiguous => "an ambiguous predicate",
+        ty::PredicateKind::NormalizesTo(_) => "a normalizes-to predicate",
+        ty::PredicateKind::AliasRelate(..) => "an alias-relate predicate",
+    }
+}
+
+/// Describes a list of bound variables, e.g. "2 lifetimes and 1 type".
+fn bound_vars_descr(bound_vars: &ty::List<ty::BoundVariableKind>) -> String {
+    let count = |f: fn(&ty::BoundVariableKind) -> bool| bound_vars.iter().filter(f).count();
+    let lifetimes = count(|var| matches!(var, ty::BoundVariableKind::Region(_)));
+    let types = count(|var| matches!(var, ty::BoundVariableKind::Ty(_)));
+    let consts = count(|var| matches!(var, ty::BoundVariableKind::Const));
+    let parts: Vec<_> = [(lifetimes, "lifetime"), (types, "type"), (consts, "const")]
+        .into_iter()
+        .filter(|&(n, _)| n > 0)
+        .map(|(n, kind)| format!("{n} bound {kind}{}", pluralize!(n)))
+        .collect();
+    match parts.split_last() {
+        None => "no bound variables".to_owned(),
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+    }
+}
+
 impl<'tcx> TypeError<'tcx> {
     pub fn must_include_note(self) -> bool {
         use self::TypeError::*;
         match self {
-            CyclicTy(_) | CyclicConst(_) | SafetyMismatch(_) | ConstnessMismatch(_)
-            | PolarityMismatch(_) | Mismatch | AbiMismatch(_) | FixedArraySize(_)
-            | ArgumentSorts(..) | Sorts(_) | VariadicMismatch(_) | TargetFeatureCast(_) => false,
+            CyclicTy(_)
+            | CyclicConst(_)
+            | SafetyMismatch(_)
+            | ConstnessMismatch(_)
+            | PolarityMismatch(_)
+            | Mismatch
+            | AbiMismatch(_)
+            | FixedArraySize(_)
+            | ArgumentSorts(..)
+            | TupleElementSorts(..)
+            | Sorts(_)
+            | VariadicMismatch(_)
+            | TargetFeatureCast(_)
+            | SimdLaneCount(_) => false,
 
             Mutability
             | ArgumentMutability(_)
             | TupleSize(_)
+            | SimdElement(_)
             | ArgCount
             | FieldMisMatch(..)
             | RegionsDoesNotOutlive(..)
@@ -200,10 +374,130 @@ pub fn must_include_note(self) -> bool {
             | Traits(_)
             | ProjectionMismatched(_)
             | ExistentialMismatch(_)
+            | MissingAutoTrait(_)
             | ConstMismatch(_)
-            | IntrinsicCast => true,
+            | ConstValueMismatch(..)
+            | PatternMismatch(_)
+            | InlineConstParentArgMismatch(..)
+            | ImplSubjectMismatch(_)
+            | ImplPolarityMismatch(_)
+            | ImplConstnessMismatch(_)
+            | PredicateKindMismatch(_)
+            | TermKindMismatch(_)
+            | BoundVarMismatch(_)
+            | IntrinsicCast
+            | RelateDepthLimitReached(_) => true,
         }
     }
+
+    /// Returns the expected and found type if this error is about two types of
+    /// different sorts, including ones found in the elements of tuples.
+    pub fn sorts(self) -> Option<ExpectedFound<Ty<'tcx>>> {
+        match self {
+            TypeError::Sorts(values) | TypeError::TupleElementSorts(values, _) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+/// A structured piece of explanation for a [`TypeError`].
+///
+/// Labels do not carry a span of their own. The emitter decides where they
+/// go, which is usually the span of the mismatching value.
+#[derive(Clone, Debug)]
+pub enum TypeErrorSubdiag {
+    /// A short description of the mismatch, see [`TypeError::to_string`].
+    Label(Cow<'static, str>),
+    Note(Cow<'static, str>),
+    Help(Cow<'static, str>),
+}
+
+/// The subdiagnostics of a [`TypeError`], see [`TypeError::into_subdiagnostic`].
+pub struct TypeErrorSubdiagnostic {
+    subdiags: Vec<TypeErrorSubdiag>,
+    label_span: Option<Span>,
+}
+
+impl Subdiagnostic for TypeErrorSubdiagnostic {
+    fn add_to_diag_with<G: EmissionGuarantee, F: SubdiagMessageOp<G>>(
+        self,
+        diag: &mut Diag<'_, G>,
+        f: &F,
+    ) {
+        for subdiag in self.subdiags {
+            match subdiag {
+                TypeErrorSubdiag::Label(msg) => {
+                    if let Some(span) = self.label_span {
+                        let msg = f(diag, msg.into());
+                        diag.span_label(span, msg);
+                    }
+                }
+                TypeErrorSubdiag::Note(msg) => {
+                    let msg = f(diag, msg.into());
+                    diag.note(msg);
+                }
+                TypeErrorSubdiag::Help(msg) => {
+                    let msg = f(diag, msg.into());
+                    diag.help(msg);
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> TypeError<'tcx> {
+    /// Returns the label, notes and help messages explaining this error, so
+    /// that all emitters of type mismatch errors share the same wording.
+    pub fn subdiagnostics(self, tcx: TyCtxt<'tcx>) -> Vec<TypeErrorSubdiag> {
+        use self::TypeError::*;
+        let mut subdiags = vec![TypeErrorSubdiag::Label(self.to_string(tcx))];
+        match self {
+            CyclicTy(ty) if ty.is_closure() || ty.is_coroutine() || ty.is_coroutine_closure() => {
+                subdiags.push(TypeErrorSubdiag::Note(
+                    "closures cannot capture themselves or take themselves as argument;\n\
+                     this error may be the result of a recent compiler bug-fix,\n\
+                     see issue #46062 <https://github.com/rust-lang/rust/issues/46062>\n\
+                     for more information"
+                        .into(),
+                ));
+            }
+            TargetFeatureCast(_) => {
+                subdiags.push(TypeErrorSubdiag::Note(
+                    "functions with `#[target_feature]` can only be coerced to `unsafe` function \
+                     pointers"
+                        .into(),
+                ));
+            }
+            RelateDepthLimitReached(_) => {
+                if tcx.sess.opts.unstable_opts.relate_depth_limit.is_some() {
+                    subdiags.push(TypeErrorSubdiag::Help(
+                        "consider increasing the limit passed to `-Zrelate-depth-limit`".into(),
+                    ));
+                } else {
+                    let suggested_limit = tcx.recursion_limit() * 2;
+                    subdiags.push(TypeErrorSubdiag::Help(
+                        format!(
+                            "consider increasing the recursion limit by adding a \
+                             `#![recursion_limit = \"{suggested_limit}\"]` attribute to your crate"
+                        )
+                        .into(),
+                    ));
+                }
+            }
+            _ => {}
+        }
+        subdiags
+    }
+
+    /// Returns the [`subdiagnostics`](Self::subdiagnostics) of this error as
+    /// a subdiagnostic, which puts the label at `label_span` or omits it.
+    pub fn into_subdiagnostic(
+        self,
+        tcx: TyCtxt<'tcx>,
+        label_span: Option<Span>,
+    ) -> TypeErrorSubdiagnostic {
+        TypeErrorSubdiagnostic { subdiags: self.subdiagnostics(tcx), label_span }
+    }
 }
 
 impl<'tcx> Ty<'tcx> {
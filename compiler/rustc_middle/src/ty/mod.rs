@@ -48,7 +48,7 @@
 use rustc_hir::def_id::{CrateNum, DefId, DefIdMap, LocalDefId, LocalDefIdMap};
 use rustc_index::IndexVec;
 use rustc_macros::{
-    extension, Decodable, Encodable, HashStable, TyDecodable, TyEncodable, TypeFoldable,
+    extension, Decodable, Encodable, HashStable, Relate, TyDecodable, TyEncodable, TypeFoldable,
     TypeVisitable,
 };
 use rustc_query_system::ich::StableHashingContext;
@@ -110,6 +110,7 @@
     BoundRegion, BoundRegionKind, BoundRegionKind::*, EarlyParamRegion, LateParamRegion, Region,
     RegionKind, RegionVid,
 };
+pub use self::relate::similarity;
 pub use self::rvalue_scopes::RvalueScopes;
 pub use self::sty::{
     AliasTy, Article, Binder, BoundTy, BoundTyKind, BoundVariableKind, CanonicalPolyFnSig,
@@ -269,9 +270,14 @@ pub struct ImplTraitHeader<'tcx> {
     pub safety: hir::Safety,
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug, TypeFoldable, TypeVisitable)]
+/// What an impl is for, see [`TyCtxt::impl_subject`].
+///
+/// Relating two impl subjects succeeds if the impls could be for the same
+/// thing, which requires trait impls to have the same polarity and constness.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, TypeFoldable, TypeVisitable, Relate)]
+#[relate(mismatch = ImplSubjectMismatch)]
 pub enum ImplSubject<'tcx> {
-    Trait(TraitRef<'tcx>),
+    Trait(TraitRef<'tcx>, ImplPolarity, hir::Constness),
     Inherent(Ty<'tcx>),
 }
 
@@ -2103,6 +2109,7 @@ pub fn provide(providers: &mut Providers) {
     inhabitedness::provide(providers);
     util::provide(providers);
     print::provide(providers);
+    relate::provide(providers);
     super::util::bug::provide(providers);
     super::middle::provide(providers);
     *providers = Providers {
@@ -7,19 +7,128 @@
 use crate::ty::error::{ExpectedFound, TypeError};
 use crate::ty::{
     self, ExistentialPredicate, ExistentialPredicateStableCmpExt as _, GenericArg, GenericArgKind,
-    GenericArgsRef, ImplSubject, Term, TermKind, Ty, TyCtxt, TypeFoldable,
+    GenericArgsRef, Term, TermKind, Ty, TyCtxt, TypeFoldable, TypeVisitableExt, Upcast,
 };
+use rustc_data_structures::stack::ensure_sufficient_stack;
 use rustc_hir as hir;
+use rustc_hir::def::DefKind;
 use rustc_hir::def_id::DefId;
-use rustc_macros::TypeVisitable;
+use rustc_span::symbol::Symbol;
+use rustc_target::abi::{FieldIdx, VariantIdx};
 use rustc_target::spec::abi;
+use std::cmp::Ordering;
+use std::fmt::Write as _;
 use std::iter;
 use tracing::{debug, instrument};
 
 use super::Pattern;
 
+mod api_diff;
+mod bindings;
+mod cache;
+mod capabilities;
+mod const_expr;
+mod context;
+mod depth;
+mod diff;
+mod dump;
+mod erased;
+mod matching;
+mod modulo_regions;
+mod pair_walk;
+mod rule;
+mod similarity;
+mod stable_hash;
+mod stats;
+mod structural_eq;
+mod validate;
+mod widening;
+
+pub use self::api_diff::{api_types_compatible, IgnoreDefaultedArgs};
+pub use self::bindings::{Bindings, RecordBindings};
+use self::cache::{cached_item_args, cached_tys};
+pub use self::cache::RelateCache;
+pub use self::capabilities::RelateCapabilities;
+pub use self::const_expr::normalize_const_expr;
+pub use self::context::{has_relate_context, print_relate_context, with_relate_context};
+use self::depth::{record_relate_width, RelateDepthGuard};
+pub use self::depth::with_fresh_relate_depth;
+pub use self::diff::{diff_tys, DiffRelation, TypeDiff, TypeDiffLeaf};
+pub use self::dump::DumpRelation;
+pub use self::erased::erased_types_equal;
+pub use self::matching::Match;
+pub(super) use self::modulo_regions::provide;
+pub use self::pair_walk::{pair_walk, AlignedPair};
+use self::rule::trace_rule;
+pub use self::rule::RelateRule;
+pub use self::similarity::{similarity, SimilarityScorer};
+pub use self::stable_hash::debug_check_stable_hash_consistency;
+use self::stats::{record_relate_result, ExistentialPredicatesTimer};
+pub use self::structural_eq::{trait_refs_eq_ignoring_self, StructuralEq};
+use self::validate::validate_relate;
+pub use self::widening::{is_lossless_widening, LosslessWidening, NumericWidening};
+
 pub type RelateResult<'tcx, T> = Result<T, TypeError<'tcx>>;
 
+/// Whether aliases should be related structurally or not, see
+/// [`TypeRelation::structurally_relate_aliases`].
+///
+/// This should always be `No` unless in a few special-cases when
+/// instantiating canonical responses and in the new solver. Each
+/// such case should have a comment explaining why it is used.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StructurallyRelateAliases {
+    Yes,
+    No,
+}
+
+/// A step from a value to one of its components, used to attach the position
+/// of a mismatch to the error returned when relating that component.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RelatePathSegment {
+    /// The `i`th input of a function signature.
+    FnArgument(usize),
+    /// The `i`th element of a tuple.
+    TupleElement(usize),
+}
+
+/// Combinators for enriching the errors of nested relations with the position
+/// at which they occurred, so that positional impls don't have to remap the
+/// errors by hand.
+pub trait RelateResultExt<'tcx>: Sized {
+    /// Records that the error, if any, happened while relating the `i`th
+    /// argument of a function signature.
+    fn with_arg_index(self, i: usize) -> Self {
+        self.with_path_segment(RelatePathSegment::FnArgument(i))
+    }
+
+    /// Records that the error, if any, happened at `segment`. Errors which
+    /// cannot carry that kind of position are returned unchanged.
+    fn with_path_segment(self, segment: RelatePathSegment) -> Self;
+}
+
+impl<'tcx, T> RelateResultExt<'tcx> for RelateResult<'tcx, T> {
+    fn with_path_segment(self, segment: RelatePathSegment) -> Self {
+        self.map_err(|err| match (segment, err) {
+            (
+                RelatePathSegment::FnArgument(i),
+                TypeError::Sorts(exp_found)
+                | TypeError::ArgumentSorts(exp_found, _)
+                | TypeError::TupleElementSorts(exp_found, _),
+            ) => TypeError::ArgumentSorts(exp_found, i),
+            (
+                RelatePathSegment::FnArgument(i),
+                TypeError::Mutability | TypeError::ArgumentMutability(_),
+            ) => TypeError::ArgumentMutability(i),
+            (
+                RelatePathSegment::TupleElement(i),
+                TypeError::Sorts(exp_found) | TypeError::TupleElementSorts(exp_found, _),
+            ) => TypeError::TupleElementSorts(exp_found, i),
+            (_, err) => err,
+        })
+    }
+}
+
 pub trait TypeRelation<'tcx>: Sized {
     fn tcx(&self) -> TyCtxt<'tcx>;
 
@@ -28,7 +137,10 @@ pub trait TypeRelation<'tcx>: Sized {
 
     /// Generic relation routine suitable for most anything.
     fn relate<T: Relate<'tcx>>(&mut self, a: T, b: T) -> RelateResult<'tcx, T> {
-        Relate::relate(self, a, b)
+        let result = Relate::relate(self, a, b);
+        record_relate_result(self.tcx(), self.tag(), &result);
+        validate_relate(self, a, b, &result);
+        result
     }
 
     /// Relate the two args for the given item. The default
@@ -45,9 +157,10 @@ fn relate_item_args(
             item_def_id, a_arg, b_arg
         );
 
-        let tcx = self.tcx();
-        let opt_variances = tcx.variances_of(item_def_id);
-        relate_args_with_variances(self, item_def_id, opt_variances, a_arg, b_arg, true)
+        cached_item_args(self, item_def_id, a_arg, b_arg, |relation| {
+            let opt_variances = relation.tcx().variances_of(item_def_id);
+            relate_args_with_variances(relation, item_def_id, opt_variances, a_arg, b_arg)
+        })
     }
 
     /// Switch variance for the purpose of relating `a` and `b`.
@@ -59,6 +172,104 @@ fn relate_with_variance<T: Relate<'tcx>>(
         b: T,
     ) -> RelateResult<'tcx, T>;
 
+    /// Whether [`structurally_relate_tys`] should relate fresh types, see
+    /// [`ty::FreshTy`], by their index instead of treating them as a bug.
+    ///
+    /// Relations probing values whose inference variables have been freshened,
+    /// e.g. to check whether two cached obligations may apply to the same
+    /// types, can opt into this instead of walking the types themselves.
+    /// Non-fresh inference variables still have to be handled by the caller.
+    fn relate_fresh_tys_by_index(&self) -> bool {
+        false
+    }
+
+    /// Returns the type `alias` normalizes to, if this relation is able to
+    /// normalize it.
+    ///
+    /// [`structurally_relate_tys`] consults this before reporting a mismatch
+    /// involving an alias, so that relations used outside of an inference
+    /// context can relate e.g. `<T as Trait>::Assoc` to its normalized form.
+    fn normalize_alias_hint(&mut self, _alias: ty::AliasTy<'tcx>) -> Option<Ty<'tcx>> {
+        None
+    }
+
+    /// Whether this relation relates aliases purely structurally, i.e. by
+    /// their `DefId` and generic args.
+    ///
+    /// With [`StructurallyRelateAliases::Yes`], [`structurally_relate_tys`]
+    /// reports a mismatch involving an alias right away, without consulting
+    /// [`TypeRelation::normalize_alias_hint`]. Relations inside of an inference
+    /// context additionally use this to decide whether to relate aliases
+    /// themselves or to emit an obligation relating them.
+    fn structurally_relate_aliases(&self) -> StructurallyRelateAliases {
+        StructurallyRelateAliases::No
+    }
+
+    /// Whether [`structurally_relate_consts`] should relate const expressions
+    /// modulo simple algebraic identities, e.g. relate `N + 1` to `1 + N`, see
+    /// [`normalize_const_expr`]. This only has an effect with the
+    /// `generic_const_exprs` feature.
+    fn normalize_const_exprs(&self) -> bool {
+        false
+    }
+
+    /// Returns the list to record mismatches in, if this relation wants to
+    /// find all mismatches instead of stopping at the first one.
+    ///
+    /// When relating the components of a value, e.g. the generic args of an
+    /// ADT, the elements of a tuple, or the inputs of a function signature,
+    /// a mismatch in one of them is recorded here and the remaining ones are
+    /// still related. The value as a whole still fails to relate with the
+    /// first mismatch, see [`relate_sibling`].
+    fn collect_errors(&mut self) -> Option<&mut Vec<TypeError<'tcx>>> {
+        None
+    }
+
+    /// Returns the capabilities of this relation, which the structural
+    /// functions consult to decide how to handle the values they encounter.
+    fn capabilities(&self) -> RelateCapabilities {
+        RelateCapabilities::default()
+    }
+
+    /// Returns the cache to memoize the results of [`structurally_relate_tys`]
+    /// and [`TypeRelation::relate_item_args`] in, together with the current
+    /// ambient variance, which is part of the cache key.
+    ///
+    /// Only relations whose results depend on nothing but the values being
+    /// related and the ambient variance may return a cache. Relations with
+    /// side effects, e.g. ones which register region constraints or record
+    /// the errors of all components, must not.
+    fn cache(&mut self) -> Option<(ty::Variance, &mut RelateCache<'tcx>)> {
+        None
+    }
+
+    /// Whether relating two equal values may return right away, without
+    /// visiting their components, as long as they contain no inference
+    /// variables, no escaping bound variables, and no free regions, if this
+    /// relation is [`RelateCapabilities::REGION_SENSITIVE`].
+    ///
+    /// Relations which have to see every component, e.g. to record bindings
+    /// or to generalize them, must opt out.
+    fn can_shortcircuit_on_equality(&self) -> bool {
+        true
+    }
+
+    /// Runs `f`, rolling back all of its side effects, so that
+    /// `-Zvalidate-relate` can relate values without affecting the outcome
+    /// of the relation being validated.
+    ///
+    /// Returns `None` if this relation can't roll back its side effects, in
+    /// which case it isn't validated, which is the default.
+    fn validation_probe<T>(&mut self, _f: impl FnOnce(&mut Self) -> T) -> Option<T> {
+        None
+    }
+
+    /// Whether this relation currently relates values for equality, in which
+    /// case `-Zvalidate-relate` checks that it is symmetric.
+    fn relates_for_equality(&self) -> bool {
+        false
+    }
+
     // Overridable relations. You shouldn't typically call these
     // directly, instead call `relate()`, which in turn calls
     // these. This is both more uniform but also allows us to add
@@ -67,17 +278,46 @@ fn relate_with_variance<T: Relate<'tcx>>(
 
     fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>>;
 
+    /// Relates two regions. By default, regions are only related if they
+    /// are equal, which is what most relations outside of type inference want,
+    /// or always if the relation isn't [`RelateCapabilities::REGION_SENSITIVE`].
     fn regions(
         &mut self,
         a: ty::Region<'tcx>,
         b: ty::Region<'tcx>,
-    ) -> RelateResult<'tcx, ty::Region<'tcx>>;
+    ) -> RelateResult<'tcx, ty::Region<'tcx>> {
+        if a == b || !self.capabilities().contains(RelateCapabilities::REGION_SENSITIVE) {
+            Ok(a)
+        } else {
+            Err(TypeError::Mismatch)
+        }
+    }
 
+    /// Relates two constants. By default, constants are related structurally,
+    /// see [`structurally_relate_consts`]. Relations which may encounter
+    /// inference variables have to override this.
     fn consts(
         &mut self,
         a: ty::Const<'tcx>,
         b: ty::Const<'tcx>,
-    ) -> RelateResult<'tcx, ty::Const<'tcx>>;
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        structurally_relate_consts(self, a, b)
+    }
+
+    /// Relates the safeties of two function signatures. By default, they have
+    /// to be equal. Relations which don't require equality, e.g. subtyping,
+    /// may override this to accept a safe function where an unsafe one is
+    /// expected.
+    fn safeties(&mut self, a: hir::Safety, b: hir::Safety) -> RelateResult<'tcx, hir::Safety> {
+        if a != b { Err(TypeError::SafetyMismatch(expected_found(a, b))) } else { Ok(a) }
+    }
+
+    /// Relates the ABIs of two function signatures. By default, they have to
+    /// be equal. Relations which don't require equality may override this to
+    /// accept ABIs which are compatible in the direction they relate.
+    fn abis(&mut self, a: abi::Abi, b: abi::Abi) -> RelateResult<'tcx, abi::Abi> {
+        if a == b { Ok(a) } else { Err(TypeError::AbiMismatch(expected_found(a, b))) }
+    }
 
     fn binders<T>(
         &mut self,
@@ -105,23 +345,53 @@ pub fn relate_args_invariantly<'tcx, R: TypeRelation<'tcx>>(
     a_arg: GenericArgsRef<'tcx>,
     b_arg: GenericArgsRef<'tcx>,
 ) -> RelateResult<'tcx, GenericArgsRef<'tcx>> {
-    relation.tcx().mk_args_from_iter(iter::zip(a_arg, b_arg).map(|(a, b)| {
-        relation.relate_with_variance(ty::Invariant, ty::VarianceDiagInfo::default(), a, b)
-    }))
+    if shortcircuit_on_equality(relation, a_arg, b_arg) {
+        return Ok(a_arg);
+    }
+    if let Some(args) = args_with_error(a_arg, b_arg) {
+        return Ok(args);
+    }
+
+    let tcx = relation.tcx();
+    let mut first_err = None;
+    let args = tcx.mk_args_from_iter(iter::zip(a_arg, b_arg).enumerate().map(|(i, (a, b))| {
+        if !same_generic_arg_kind(a, b) {
+            return Err(GenericArgKindMismatch { owner: None, index: i, a_arg, b_arg }.report(tcx));
+        }
+        relate_sibling(relation, &mut first_err, a, |relation| {
+            relation.relate_with_variance(ty::Invariant, ty::VarianceDiagInfo::default(), a, b)
+        })
+    }))?;
+    first_err.map_or(Ok(args), Err)
 }
 
+/// Relates the args of `ty_def_id` with the given variances.
+///
+/// If the relation [`RelateCapabilities::WANTS_DIAGNOSTICS`], the type of
+/// `ty_def_id` is attached to the variance info of invariant args. This is
+/// never done for opaque types, as fetching their type may cause a cycle.
 pub fn relate_args_with_variances<'tcx, R: TypeRelation<'tcx>>(
     relation: &mut R,
     ty_def_id: DefId,
     variances: &[ty::Variance],
     a_arg: GenericArgsRef<'tcx>,
     b_arg: GenericArgsRef<'tcx>,
-    fetch_ty_for_diag: bool,
 ) -> RelateResult<'tcx, GenericArgsRef<'tcx>> {
+    if let Some(args) = args_with_error(a_arg, b_arg) {
+        return Ok(args);
+    }
+
     let tcx = relation.tcx();
+    let fetch_ty_for_diag = relation.capabilities().contains(RelateCapabilities::WANTS_DIAGNOSTICS)
+        && tcx.def_kind(ty_def_id) != DefKind::OpaqueTy;
 
     let mut cached_ty = None;
+    let mut first_err = None;
     let params = iter::zip(a_arg, b_arg).enumerate().map(|(i, (a, b))| {
+        if !same_generic_arg_kind(a, b) {
+            return Err(GenericArgKindMismatch { owner: Some(ty_def_id), index: i, a_arg, b_arg }
+                .report(tcx));
+        }
         let variance = variances[i];
         let variance_info = if variance == ty::Invariant && fetch_ty_for_diag {
             let ty =
@@ -130,10 +400,156 @@ pub fn relate_args_with_variances<'tcx, R: TypeRelation<'tcx>>(
         } else {
             ty::VarianceDiagInfo::default()
         };
-        relation.relate_with_variance(variance, variance_info, a, b)
+        relate_sibling(relation, &mut first_err, a, |relation| {
+            relation.relate_with_variance(variance, variance_info, a, b)
+        })
     });
 
-    tcx.mk_args_from_iter(params)
+    let args = tcx.mk_args_from_iter(params)?;
+    first_err.map_or(Ok(args), Err)
+}
+
+/// Returns whether relating `a` to `b` may return `a` without visiting its
+/// components, see [`TypeRelation::can_shortcircuit_on_equality`].
+///
+/// Interned values are compared by pointer, and the flags are cached, so this
+/// is cheap compared to walking the values.
+#[inline]
+fn shortcircuit_on_equality<'tcx, R, T>(relation: &R, a: T, b: T) -> bool
+where
+    R: TypeRelation<'tcx>,
+    T: TypeVisitableExt<TyCtxt<'tcx>> + PartialEq,
+{
+    a == b
+        && relation.can_shortcircuit_on_equality()
+        && !a.has_infer()
+        && !a.has_escaping_bound_vars()
+        && !(a.has_free_regions()
+            && relation.capabilities().contains(RelateCapabilities::REGION_SENSITIVE))
+}
+
+/// Returns the result of relating `a_arg` and `b_arg` if either of them
+/// references an error, e.g. a `ConstKind::Error` nested in one of the args.
+///
+/// Relating such args structurally would only result in mismatches caused by
+/// the error, so we relate them to the erroneous args right away. This is cheap
+/// to check, as it only looks at the cached flags of the args.
+///
+/// Args containing inference variables are still related structurally, as
+/// relations such as the generalizer have to see all of them.
+fn args_with_error<'tcx>(
+    a_arg: GenericArgsRef<'tcx>,
+    b_arg: GenericArgsRef<'tcx>,
+) -> Option<GenericArgsRef<'tcx>> {
+    if (a_arg, b_arg).has_infer() {
+        None
+    } else if a_arg.references_error() {
+        Some(a_arg)
+    } else if b_arg.references_error() {
+        Some(b_arg)
+    } else {
+        None
+    }
+}
+
+/// Relates one of several sibling components of a value using `relate`.
+///
+/// If the relation collects errors, see [`TypeRelation::collect_errors`], a
+/// mismatch is recorded and replaced by `a`, so that the caller can go on
+/// relating the remaining siblings. The first mismatch is stored in
+/// `first_err`, which the caller has to return once it is done.
+pub fn relate_sibling<'tcx, R: TypeRelation<'tcx>, T>(
+    relation: &mut R,
+    first_err: &mut Option<TypeError<'tcx>>,
+    a: T,
+    relate: impl FnOnce(&mut R) -> RelateResult<'tcx, T>,
+) -> RelateResult<'tcx, T> {
+    let Some(num_collected) = relation.collect_errors().map(|errors| errors.len()) else {
+        return relate(relation);
+    };
+
+    match relate(relation) {
+        // Stop immediately if we're about to overflow.
+        Err(err @ TypeError::RelateDepthLimitReached(_)) => Err(err),
+        Err(err) => {
+            let errors = relation.collect_errors().unwrap();
+            // Mismatches of nested siblings have already been recorded.
+            if errors.len() == num_collected {
+                errors.push(err);
+            }
+            first_err.get_or_insert(err);
+            Ok(a)
+        }
+        Ok(value) => Ok(value),
+    }
+}
+
+/// Relates two binders whose bound variables may be named or ordered
+/// differently, for relations which don't need to track placeholders.
+///
+/// The bound variables of both binders are anonymized and renumbered in the
+/// order in which they first occur, see [`TyCtxt::anonymize_bound_vars`]. If
+/// the resulting lists of bound variables don't have the same kinds, the
+/// binders fail to relate with [`TypeError::BoundVarMismatch`]. Otherwise,
+/// each bound variable of `a` corresponds to the bound variable of `b` with
+/// the same index, so relating the bound values is the same as instantiating
+/// both with the same placeholders and relating the instantiations.
+pub fn relate_higher_ranked<'tcx, R: TypeRelation<'tcx>, T: Relate<'tcx>>(
+    relation: &mut R,
+    a: ty::Binder<'tcx, T>,
+    b: ty::Binder<'tcx, T>,
+) -> RelateResult<'tcx, ty::Binder<'tcx, T>> {
+    let tcx = relation.tcx();
+    let (a, b) = (tcx.anonymize_bound_vars(a), tcx.anonymize_bound_vars(b));
+    if a.bound_vars() != b.bound_vars() {
+        return Err(TypeError::BoundVarMismatch(expected_found(a.bound_vars(), b.bound_vars())));
+    }
+    Ok(a.rebind(relation.relate(a.skip_binder(), b.skip_binder())?))
+}
+
+fn same_generic_arg_kind<'tcx>(a: GenericArg<'tcx>, b: GenericArg<'tcx>) -> bool {
+    matches!(
+        (a.unpack(), b.unpack()),
+        (GenericArgKind::Lifetime(_), GenericArgKind::Lifetime(_))
+            | (GenericArgKind::Type(_), GenericArgKind::Type(_))
+            | (GenericArgKind::Const(_), GenericArgKind::Const(_))
+    )
+}
+
+/// An attempt to relate two generic args of different kinds.
+///
+/// This is usually the consequence of an error which has already been reported,
+/// e.g. a wrong number of generic args, and is an internal error otherwise.
+struct GenericArgKindMismatch<'a, 'tcx> {
+    /// The item the args belong to, if known.
+    owner: Option<DefId>,
+    /// The index of the mismatching args.
+    index: usize,
+    a_arg: &'a [GenericArg<'tcx>],
+    b_arg: &'a [GenericArg<'tcx>],
+}
+
+impl<'tcx> GenericArgKindMismatch<'_, 'tcx> {
+    #[cold]
+    #[inline(never)]
+    fn report(self, tcx: TyCtxt<'tcx>) -> TypeError<'tcx> {
+        let GenericArgKindMismatch { owner, index, a_arg, b_arg } = self;
+        let owner = match owner {
+            Some(owner) => format!("`{}`", tcx.def_path_str(owner)),
+            None => "an unknown item".to_string(),
+        };
+        let msg = format!(
+            "cannot relate generic args of different kinds: `{:?}` and `{:?}` at index {index} \
+            of the args of {owner}, {a_arg:?} and {b_arg:?}",
+            a_arg[index], b_arg[index],
+        );
+        if tcx.dcx().has_errors().is_some() {
+            tcx.dcx().delayed_bug(msg);
+            TypeError::Mismatch
+        } else {
+            bug!("{msg}")
+        }
+    }
 }
 
 impl<'tcx> Relate<'tcx> for ty::FnSig<'tcx> {
@@ -147,44 +563,42 @@ fn relate<R: TypeRelation<'tcx>>(
         if a.c_variadic != b.c_variadic {
             return Err(TypeError::VariadicMismatch(expected_found(a.c_variadic, b.c_variadic)));
         }
+        trace_rule(relation.tag(), RelateRule::FnSigSafety);
         let safety = relation.relate(a.safety, b.safety)?;
+        trace_rule(relation.tag(), RelateRule::FnSigAbi);
         let abi = relation.relate(a.abi, b.abi)?;
 
         if a.inputs().len() != b.inputs().len() {
             return Err(TypeError::ArgCount);
         }
 
+        let mut first_err = None;
         let inputs_and_output = iter::zip(a.inputs(), b.inputs())
             .map(|(&a, &b)| ((a, b), false))
             .chain(iter::once(((a.output(), b.output()), true)))
-            .map(|((a, b), is_output)| {
-                if is_output {
-                    relation.relate(a, b)
-                } else {
-                    relation.relate_with_variance(
-                        ty::Contravariant,
-                        ty::VarianceDiagInfo::default(),
-                        a,
-                        b,
-                    )
-                }
-            })
             .enumerate()
-            .map(|(i, r)| match r {
-                Err(TypeError::Sorts(exp_found) | TypeError::ArgumentSorts(exp_found, _)) => {
-                    Err(TypeError::ArgumentSorts(exp_found, i))
-                }
-                Err(TypeError::Mutability | TypeError::ArgumentMutability(_)) => {
-                    Err(TypeError::ArgumentMutability(i))
-                }
-                r => r,
+            .map(|(i, ((a, b), is_output))| {
+                relate_sibling(relation, &mut first_err, a, |relation| {
+                    let r = if is_output {
+                        trace_rule(relation.tag(), RelateRule::FnSigOutput);
+                        relation.relate(a, b)
+                    } else {
+                        trace_rule(relation.tag(), RelateRule::FnSigInput(i));
+                        relation.relate_with_variance(
+                            ty::Contravariant,
+                            ty::VarianceDiagInfo::default(),
+                            a,
+                            b,
+                        )
+                    };
+                    r.with_arg_index(i)
+                })
             });
-        Ok(ty::FnSig {
-            inputs_and_output: tcx.mk_type_list_from_iter(inputs_and_output)?,
-            c_variadic: a.c_variadic,
-            safety,
-            abi,
-        })
+        let inputs_and_output = tcx.mk_type_list_from_iter(inputs_and_output)?;
+        if let Some(err) = first_err {
+            return Err(err);
+        }
+        Ok(ty::FnSig { inputs_and_output, c_variadic: a.c_variadic, safety, abi })
     }
 }
 
@@ -199,22 +613,24 @@ fn relate<R: TypeRelation<'tcx>>(
 }
 
 impl<'tcx> Relate<'tcx> for hir::Safety {
+    #[inline]
     fn relate<R: TypeRelation<'tcx>>(
-        _relation: &mut R,
+        relation: &mut R,
         a: hir::Safety,
         b: hir::Safety,
     ) -> RelateResult<'tcx, hir::Safety> {
-        if a != b { Err(TypeError::SafetyMismatch(expected_found(a, b))) } else { Ok(a) }
+        relation.safeties(a, b)
     }
 }
 
 impl<'tcx> Relate<'tcx> for abi::Abi {
+    #[inline]
     fn relate<R: TypeRelation<'tcx>>(
-        _relation: &mut R,
+        relation: &mut R,
         a: abi::Abi,
         b: abi::Abi,
     ) -> RelateResult<'tcx, abi::Abi> {
-        if a == b { Ok(a) } else { Err(TypeError::AbiMismatch(expected_found(a, b))) }
+        relation.abis(a, b)
     }
 }
 
@@ -227,6 +643,7 @@ fn relate<R: TypeRelation<'tcx>>(
         if a.def_id != b.def_id {
             Err(TypeError::ProjectionMismatched(expected_found(a.def_id, b.def_id)))
         } else {
+            trace_rule(relation.tag(), RelateRule::AliasArgs);
             let args = match a.kind(relation.tcx()) {
                 ty::Opaque => relate_args_with_variances(
                     relation,
@@ -234,7 +651,6 @@ fn relate<R: TypeRelation<'tcx>>(
                     relation.tcx().variances_of(a.def_id),
                     a.args,
                     b.args,
-                    false, // do not fetch `type_of(a_def_id)`, as it will cause a cycle
                 )?,
                 ty::Projection | ty::Weak | ty::Inherent => {
                     relate_args_invariantly(relation, a.args, b.args)?
@@ -254,6 +670,7 @@ fn relate<R: TypeRelation<'tcx>>(
         if a.def_id != b.def_id {
             Err(TypeError::ProjectionMismatched(expected_found(a.def_id, b.def_id)))
         } else {
+            trace_rule(relation.tag(), RelateRule::AliasArgs);
             let args = match a.kind(relation.tcx()) {
                 ty::AliasTermKind::OpaqueTy => relate_args_with_variances(
                     relation,
@@ -261,7 +678,6 @@ fn relate<R: TypeRelation<'tcx>>(
                     relation.tcx().variances_of(a.def_id),
                     a.args,
                     b.args,
-                    false, // do not fetch `type_of(a_def_id)`, as it will cause a cycle
                 )?,
                 ty::AliasTermKind::ProjectionTy
                 | ty::AliasTermKind::WeakTy
@@ -312,12 +728,33 @@ fn relate<R: TypeRelation<'tcx>>(
         if a.def_id != b.def_id {
             Err(TypeError::Traits(expected_found(a.def_id, b.def_id)))
         } else {
+            trace_rule(relation.tag(), RelateRule::TraitRefArgs);
             let args = relate_args_invariantly(relation, a.args, b.args)?;
             Ok(ty::TraitRef::new(relation.tcx(), a.def_id, args))
         }
     }
 }
 
+impl<'tcx> Relate<'tcx> for ty::ImplPolarity {
+    fn relate<R: TypeRelation<'tcx>>(
+        _relation: &mut R,
+        a: ty::ImplPolarity,
+        b: ty::ImplPolarity,
+    ) -> RelateResult<'tcx, ty::ImplPolarity> {
+        if a != b { Err(TypeError::ImplPolarityMismatch(expected_found(a, b))) } else { Ok(a) }
+    }
+}
+
+impl<'tcx> Relate<'tcx> for hir::Constness {
+    fn relate<R: TypeRelation<'tcx>>(
+        _relation: &mut R,
+        a: hir::Constness,
+        b: hir::Constness,
+    ) -> RelateResult<'tcx, hir::Constness> {
+        if a != b { Err(TypeError::ImplConstnessMismatch(expected_found(a, b))) } else { Ok(a) }
+    }
+}
+
 impl<'tcx> Relate<'tcx> for ty::ExistentialTraitRef<'tcx> {
     fn relate<R: TypeRelation<'tcx>>(
         relation: &mut R,
@@ -328,53 +765,13 @@ fn relate<R: TypeRelation<'tcx>>(
         if a.def_id != b.def_id {
             Err(TypeError::Traits(expected_found(a.def_id, b.def_id)))
         } else {
+            trace_rule(relation.tag(), RelateRule::TraitRefArgs);
             let args = relate_args_invariantly(relation, a.args, b.args)?;
             Ok(ty::ExistentialTraitRef { def_id: a.def_id, args })
         }
     }
 }
 
-#[derive(PartialEq, Copy, Debug, Clone, TypeFoldable, TypeVisitable)]
-struct CoroutineWitness<'tcx>(&'tcx ty::List<Ty<'tcx>>);
-
-impl<'tcx> Relate<'tcx> for CoroutineWitness<'tcx> {
-    fn relate<R: TypeRelation<'tcx>>(
-        relation: &mut R,
-        a: CoroutineWitness<'tcx>,
-        b: CoroutineWitness<'tcx>,
-    ) -> RelateResult<'tcx, CoroutineWitness<'tcx>> {
-        assert_eq!(a.0.len(), b.0.len());
-        let tcx = relation.tcx();
-        let types =
-            tcx.mk_type_list_from_iter(iter::zip(a.0, b.0).map(|(a, b)| relation.relate(a, b)))?;
-        Ok(CoroutineWitness(types))
-    }
-}
-
-impl<'tcx> Relate<'tcx> for ImplSubject<'tcx> {
-    #[inline]
-    fn relate<R: TypeRelation<'tcx>>(
-        relation: &mut R,
-        a: ImplSubject<'tcx>,
-        b: ImplSubject<'tcx>,
-    ) -> RelateResult<'tcx, ImplSubject<'tcx>> {
-        match (a, b) {
-            (ImplSubject::Trait(trait_ref_a), ImplSubject::Trait(trait_ref_b)) => {
-                let trait_ref = ty::TraitRef::relate(relation, trait_ref_a, trait_ref_b)?;
-                Ok(ImplSubject::Trait(trait_ref))
-            }
-            (ImplSubject::Inherent(ty_a), ImplSubject::Inherent(ty_b)) => {
-                let ty = Ty::relate(relation, ty_a, ty_b)?;
-                Ok(ImplSubject::Inherent(ty))
-            }
-            (ImplSubject::Trait(_), ImplSubject::Inherent(_))
-            | (ImplSubject::Inherent(_), ImplSubject::Trait(_)) => {
-                bug!("can not relate TraitRef and Ty");
-            }
-        }
-    }
-}
-
 impl<'tcx> Relate<'tcx> for Ty<'tcx> {
     #[inline]
     fn relate<R: TypeRelation<'tcx>>(
@@ -393,26 +790,177 @@ fn relate<R: TypeRelation<'tcx>>(
         a: Self,
         b: Self,
     ) -> RelateResult<'tcx, Self> {
+        if a == b {
+            return Ok(a);
+        }
+
+        // Patterns whose bounds are known are equal if they include the same
+        // values, e.g. `0..=255` and `..` for `u8`, or `0..5` and `0..=4`.
+        let tcx = relation.tcx();
+        let can_eval = relation.capabilities().contains(RelateCapabilities::CONST_EVAL);
+        if let (Some(a_range), Some(b_range)) =
+            (included_range(tcx, a, can_eval), included_range(tcx, b, can_eval))
+        {
+            return if a_range == b_range {
+                Ok(a)
+            } else {
+                Err(TypeError::PatternMismatch(expected_found(a, b)))
+            };
+        }
+
         match (&*a, &*b) {
             (
                 &ty::PatternKind::Range { start: start_a, end: end_a, include_end: inc_a },
                 &ty::PatternKind::Range { start: start_b, end: end_b, include_end: inc_b },
             ) => {
-                // FIXME(pattern_types): make equal patterns equal (`0..=` is the same as `..=`).
-                let mut relate_opt_const = |a, b| match (a, b) {
+                let mismatch = || TypeError::PatternMismatch(expected_found(a, b));
+                if inc_a != inc_b {
+                    return Err(mismatch());
+                }
+                let mut relate_opt_const = |c_a, c_b| match (c_a, c_b) {
                     (None, None) => Ok(None),
-                    (Some(a), Some(b)) => relation.relate(a, b).map(Some),
-                    // FIXME(pattern_types): report a better error
-                    _ => Err(TypeError::Mismatch),
+                    (Some(c_a), Some(c_b)) => {
+                        relation.relate(c_a, c_b).map(Some).map_err(|err| match err {
+                            TypeError::ConstMismatch(_) | TypeError::ConstValueMismatch(..) => {
+                                mismatch()
+                            }
+                            err => err,
+                        })
+                    }
+                    _ => Err(mismatch()),
                 };
                 let start = relate_opt_const(start_a, start_b)?;
                 let end = relate_opt_const(end_a, end_b)?;
-                if inc_a != inc_b {
-                    todo!()
-                }
-                Ok(relation.tcx().mk_pat(ty::PatternKind::Range { start, end, include_end: inc_a }))
+                Ok(tcx.mk_pat(ty::PatternKind::Range { start, end, include_end: inc_a }))
+            }
+        }
+    }
+}
+
+/// Returns the type of the bounds of the range pattern `pat` together with
+/// the first and the last value it includes, as bits, if all of its bounds
+/// are known integers.
+///
+/// Unevaluated bounds are only evaluated if `can_eval` is set.
+fn included_range<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    pat: Pattern<'tcx>,
+    can_eval: bool,
+) -> Option<(Ty<'tcx>, u128, u128)> {
+    let eval_bound = |bound: Option<ty::Const<'tcx>>| -> Option<Option<(Ty<'tcx>, ty::ScalarInt)>> {
+        let Some(bound) = bound else {
+            return Some(None);
+        };
+        let value = match bound.kind() {
+            ty::ConstKind::Value(ty, valtree) => (ty, valtree.try_to_scalar_int()?),
+            ty::ConstKind::Unevaluated(_)
+                if can_eval
+                    && !bound.has_param()
+                    && !bound.has_infer()
+                    && !bound.has_escaping_bound_vars() =>
+            {
+                bound.try_eval_scalar_int(tcx, ty::ParamEnv::reveal_all())?
+            }
+            _ => return None,
+        };
+        Some(Some(value))
+    };
+
+    let ty::PatternKind::Range { start, end, include_end } = *pat;
+    let start = eval_bound(start)?;
+    let end = eval_bound(end)?;
+    let ty = start.or(end)?.0;
+    if !matches!(ty.kind(), ty::Int(_) | ty::Uint(_)) {
+        return None;
+    }
+    let (min, max) = ty.numeric_min_and_max_as_bits(tcx)?;
+    let size = ty.primitive_size(tcx);
+    let first = match start {
+        Some((_, int)) => int.try_to_bits(size).ok()?,
+        None => min,
+    };
+    let last = match end {
+        Some((_, int)) => {
+            let end = int.try_to_bits(size).ok()?;
+            if include_end {
+                end
+            } else if end == min {
+                // The range is empty.
+                return None;
+            } else {
+                size.truncate(end.wrapping_sub(1))
             }
         }
+        None => max,
+    };
+    Some((ty, first, last))
+}
+
+/// Returns the lane count, if known, and the element type of a SIMD vector.
+fn simd_lanes<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def: ty::AdtDef<'tcx>,
+    args: GenericArgsRef<'tcx>,
+) -> Option<(Option<u64>, Ty<'tcx>)> {
+    let variant = def.non_enum_variant();
+    let field_ty = variant.fields.iter().next()?.ty(tcx, args);
+    match *field_ty.kind() {
+        // See `Ty::simd_size_and_type`, which we can't use as the length may be generic.
+        ty::Array(elem_ty, len) => Some((len.try_to_target_usize(tcx), elem_ty)),
+        _ => Some((Some(variant.fields.len() as u64), field_ty)),
+    }
+}
+
+/// Replaces `err`, the error returned when relating the args of two uses of
+/// the SIMD vector `def`, with an error saying whether their lane counts or
+/// their element types differ, if possible.
+fn simd_mismatch<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def: ty::AdtDef<'tcx>,
+    a_args: GenericArgsRef<'tcx>,
+    b_args: GenericArgsRef<'tcx>,
+    err: TypeError<'tcx>,
+) -> TypeError<'tcx> {
+    if err.involves_regions() || matches!(err, TypeError::RelateDepthLimitReached(_)) {
+        return err;
+    }
+    let (Some((a_lanes, a_elem)), Some((b_lanes, b_elem))) =
+        (simd_lanes(tcx, def, a_args), simd_lanes(tcx, def, b_args))
+    else {
+        return err;
+    };
+
+    match (a_lanes, b_lanes) {
+        (Some(a_lanes), Some(b_lanes)) if a_lanes != b_lanes => {
+            TypeError::SimdLaneCount(expected_found(a_lanes, b_lanes))
+        }
+        _ if a_elem != b_elem => TypeError::SimdElement(expected_found(a_elem, b_elem)),
+        _ => err,
+    }
+}
+
+/// Returns the value of the array length `len`, if it is known.
+///
+/// Unevaluated lengths are only evaluated if `can_eval` is set and they don't
+/// mention any generic parameters or inference variables, so evaluating them
+/// doesn't need a `ParamEnv`. This only happens once relating two lengths
+/// failed, so it doesn't slow down the common case.
+fn array_len_for_diagnostics<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    len: ty::Const<'tcx>,
+    can_eval: bool,
+) -> Option<u64> {
+    match len.kind() {
+        ty::ConstKind::Value(..) => len.try_to_target_usize(tcx),
+        ty::ConstKind::Unevaluated(_)
+            if can_eval
+                && !len.has_param()
+                && !len.has_infer()
+                && !len.has_escaping_bound_vars() =>
+        {
+            len.try_eval_target_usize(tcx, ty::ParamEnv::reveal_all())
+        }
+        _ => None,
     }
 }
 
@@ -424,19 +972,51 @@ pub fn structurally_relate_tys<'tcx, R: TypeRelation<'tcx>>(
     relation: &mut R,
     a: Ty<'tcx>,
     b: Ty<'tcx>,
+) -> RelateResult<'tcx, Ty<'tcx>> {
+    if shortcircuit_on_equality(relation, a, b) {
+        return Ok(a);
+    }
+
+    // The depth limit may be raised well beyond what fits on the default stack.
+    cached_tys(relation, a, b, |relation| {
+        ensure_sufficient_stack(|| structurally_relate_tys_uncached(relation, a, b))
+    })
+}
+
+fn structurally_relate_tys_uncached<'tcx, R: TypeRelation<'tcx>>(
+    relation: &mut R,
+    a: Ty<'tcx>,
+    b: Ty<'tcx>,
 ) -> RelateResult<'tcx, Ty<'tcx>> {
     let tcx = relation.tcx();
+    let _depth = RelateDepthGuard::enter(tcx, &(a, b))?;
+    record_relate_width(tcx, a, b);
+    let is_fresh_or_not_infer = |ty: Ty<'tcx>| ty.is_fresh() || !matches!(ty.kind(), ty::Infer(_));
     match (a.kind(), b.kind()) {
+        (&ty::Infer(_), _) | (_, &ty::Infer(_))
+            if relation.relate_fresh_tys_by_index()
+                && is_fresh_or_not_infer(a)
+                && is_fresh_or_not_infer(b) =>
+        {
+            if a == b { Ok(a) } else { Err(TypeError::Sorts(expected_found(a, b))) }
+        }
+
         (&ty::Infer(_), _) | (_, &ty::Infer(_)) => {
-            // The caller should handle these cases!
-            bug!("var types encountered in structurally_relate_tys")
+            if !relation.capabilities().contains(RelateCapabilities::INFER_MISMATCH) {
+                // The caller should handle these cases!
+                bug!("var types encountered in structurally_relate_tys")
+            }
+            Err(TypeError::Sorts(expected_found(a, b)))
         }
 
         (ty::Bound(..), _) | (_, ty::Bound(..)) => {
             bug!("bound types encountered in structurally_relate_tys")
         }
 
-        (&ty::Error(guar), _) | (_, &ty::Error(guar)) => Ok(Ty::new_error(tcx, guar)),
+        (&ty::Error(guar), _) | (_, &ty::Error(guar)) => {
+            trace_rule(relation.tag(), RelateRule::Error);
+            Ok(Ty::new_error(tcx, guar))
+        }
 
         (&ty::Never, _)
         | (&ty::Char, _)
@@ -447,38 +1027,59 @@ pub fn structurally_relate_tys<'tcx, R: TypeRelation<'tcx>>(
         | (&ty::Str, _)
             if a == b =>
         {
+            trace_rule(relation.tag(), RelateRule::Primitive);
             Ok(a)
         }
 
         (ty::Param(a_p), ty::Param(b_p)) if a_p.index == b_p.index => {
             debug_assert_eq!(a_p.name, b_p.name, "param types with same index differ in name");
+            trace_rule(relation.tag(), RelateRule::Param);
             Ok(a)
         }
 
-        (ty::Placeholder(p1), ty::Placeholder(p2)) if p1 == p2 => Ok(a),
+        (ty::Placeholder(p1), ty::Placeholder(p2)) if p1 == p2 => {
+            trace_rule(relation.tag(), RelateRule::Placeholder);
+            Ok(a)
+        }
 
         (&ty::Adt(a_def, a_args), &ty::Adt(b_def, b_args)) if a_def == b_def => {
-            let args = relation.relate_item_args(a_def.did(), a_args, b_args)?;
+            trace_rule(relation.tag(), RelateRule::AdtArgs);
+            let args = relation.relate_item_args(a_def.did(), a_args, b_args).map_err(|err| {
+                // For SIMD vectors, explain whether the lane count or the element
+                // type differs, instead of reporting a mismatch of generic args.
+                if a_def.repr().simd() {
+                    simd_mismatch(tcx, a_def, a_args, b_args, err)
+                } else {
+                    err
+                }
+            })?;
             Ok(Ty::new_adt(tcx, a_def, args))
         }
 
-        (&ty::Foreign(a_id), &ty::Foreign(b_id)) if a_id == b_id => Ok(Ty::new_foreign(tcx, a_id)),
+        (&ty::Foreign(a_id), &ty::Foreign(b_id)) if a_id == b_id => {
+            trace_rule(relation.tag(), RelateRule::Foreign);
+            Ok(Ty::new_foreign(tcx, a_id))
+        }
 
         (&ty::Dynamic(a_obj, a_region, a_repr), &ty::Dynamic(b_obj, b_region, b_repr))
             if a_repr == b_repr =>
         {
-            Ok(Ty::new_dynamic(
-                tcx,
-                relation.relate(a_obj, b_obj)?,
-                relation.relate(a_region, b_region)?,
-                a_repr,
-            ))
+            let mut first_err = None;
+            let obj = relate_sibling(relation, &mut first_err, a_obj, |relation| {
+                relation.relate(a_obj, b_obj)
+            })?;
+            trace_rule(relation.tag(), RelateRule::DynRegion);
+            let region = relate_sibling(relation, &mut first_err, a_region, |relation| {
+                relation.relate(a_region, b_region)
+            })?;
+            first_err.map_or(Ok(Ty::new_dynamic(tcx, obj, region, a_repr)), Err)
         }
 
         (&ty::Coroutine(a_id, a_args), &ty::Coroutine(b_id, b_args)) if a_id == b_id => {
             // All Coroutine types with the same id represent
             // the (anonymous) type of the same coroutine expression. So
             // all of their regions should be equated.
+            trace_rule(relation.tag(), RelateRule::CoroutineArgs);
             let args = relate_args_invariantly(relation, a_args, b_args)?;
             Ok(Ty::new_coroutine(tcx, a_id, args))
         }
@@ -489,6 +1090,7 @@ pub fn structurally_relate_tys<'tcx, R: TypeRelation<'tcx>>(
             // All CoroutineWitness types with the same id represent
             // the (anonymous) type of the same coroutine expression. So
             // all of their regions should be equated.
+            trace_rule(relation.tag(), RelateRule::CoroutineWitnessArgs);
             let args = relate_args_invariantly(relation, a_args, b_args)?;
             Ok(Ty::new_coroutine_witness(tcx, a_id, args))
         }
@@ -497,6 +1099,7 @@ pub fn structurally_relate_tys<'tcx, R: TypeRelation<'tcx>>(
             // All Closure types with the same id represent
             // the (anonymous) type of the same closure expression. So
             // all of their regions should be equated.
+            trace_rule(relation.tag(), RelateRule::ClosureArgs);
             let args = relate_args_invariantly(relation, a_args, b_args)?;
             Ok(Ty::new_closure(tcx, a_id, args))
         }
@@ -504,6 +1107,7 @@ pub fn structurally_relate_tys<'tcx, R: TypeRelation<'tcx>>(
         (&ty::CoroutineClosure(a_id, a_args), &ty::CoroutineClosure(b_id, b_args))
             if a_id == b_id =>
         {
+            trace_rule(relation.tag(), RelateRule::CoroutineClosureArgs);
             let args = relate_args_invariantly(relation, a_args, b_args)?;
             Ok(Ty::new_coroutine_closure(tcx, a_id, args))
         }
@@ -520,6 +1124,7 @@ pub fn structurally_relate_tys<'tcx, R: TypeRelation<'tcx>>(
                 }
             };
 
+            trace_rule(relation.tag(), RelateRule::RawPtrPointee);
             let ty = relation.relate_with_variance(variance, info, a_ty, b_ty)?;
 
             Ok(Ty::new_ptr(tcx, ty, a_mutbl))
@@ -537,43 +1142,66 @@ pub fn structurally_relate_tys<'tcx, R: TypeRelation<'tcx>>(
                 }
             };
 
-            let r = relation.relate(a_r, b_r)?;
-            let ty = relation.relate_with_variance(variance, info, a_ty, b_ty)?;
-
-            Ok(Ty::new_ref(tcx, r, ty, a_mutbl))
+            let mut first_err = None;
+            trace_rule(relation.tag(), RelateRule::RefRegion);
+            let r = relate_sibling(relation, &mut first_err, a_r, |relation| {
+                relation.relate(a_r, b_r)
+            })?;
+            trace_rule(relation.tag(), RelateRule::RefPointee);
+            let ty = relate_sibling(relation, &mut first_err, a_ty, |relation| {
+                relation.relate_with_variance(variance, info, a_ty, b_ty)
+            })?;
+
+            first_err.map_or(Ok(Ty::new_ref(tcx, r, ty, a_mutbl)), Err)
         }
 
         (&ty::Array(a_t, sz_a), &ty::Array(b_t, sz_b)) => {
-            let t = relation.relate(a_t, b_t)?;
-            match relation.relate(sz_a, sz_b) {
-                Ok(sz) => Ok(Ty::new_array_with_const_len(tcx, t, sz)),
-                Err(err) => {
+            let can_eval = relation.capabilities().contains(RelateCapabilities::CONST_EVAL);
+            let mut first_err = None;
+            trace_rule(relation.tag(), RelateRule::ArrayElement);
+            let t = relate_sibling(relation, &mut first_err, a_t, |relation| {
+                relation.relate(a_t, b_t)
+            })?;
+            trace_rule(relation.tag(), RelateRule::ArrayLen);
+            let sz = relate_sibling(relation, &mut first_err, sz_a, |relation| {
+                relation.relate(sz_a, sz_b).map_err(|err| {
                     // Check whether the lengths are both concrete/known values,
                     // but are unequal, for better diagnostics.
-                    let sz_a = sz_a.try_to_target_usize(tcx);
-                    let sz_b = sz_b.try_to_target_usize(tcx);
+                    let sz_a = array_len_for_diagnostics(tcx, sz_a, can_eval);
+                    let sz_b = array_len_for_diagnostics(tcx, sz_b, can_eval);
 
                     match (sz_a, sz_b) {
                         (Some(sz_a_val), Some(sz_b_val)) if sz_a_val != sz_b_val => {
-                            Err(TypeError::FixedArraySize(expected_found(sz_a_val, sz_b_val)))
+                            TypeError::FixedArraySize(expected_found(sz_a_val, sz_b_val))
                         }
-                        _ => Err(err),
+                        _ => err,
                     }
-                }
-            }
+                })
+            })?;
+            first_err.map_or(Ok(Ty::new_array_with_const_len(tcx, t, sz)), Err)
         }
 
         (&ty::Slice(a_t), &ty::Slice(b_t)) => {
+            trace_rule(relation.tag(), RelateRule::SliceElement);
             let t = relation.relate(a_t, b_t)?;
             Ok(Ty::new_slice(tcx, t))
         }
 
         (&ty::Tuple(as_), &ty::Tuple(bs)) => {
             if as_.len() == bs.len() {
-                Ok(Ty::new_tup_from_iter(
+                let mut first_err = None;
+                let ty = Ty::new_tup_from_iter(
                     tcx,
-                    iter::zip(as_, bs).map(|(a, b)| relation.relate(a, b)),
-                )?)
+                    iter::zip(as_, bs).enumerate().map(|(i, (a, b))| {
+                        trace_rule(relation.tag(), RelateRule::TupleElement(i));
+                        relate_sibling(relation, &mut first_err, a, |relation| {
+                            relation
+                                .relate(a, b)
+                                .with_path_segment(RelatePathSegment::TupleElement(i))
+                        })
+                    }),
+                )?;
+                first_err.map_or(Ok(ty), Err)
             } else if !(as_.is_empty() || bs.is_empty()) {
                 Err(TypeError::TupleSize(expected_found(as_.len(), bs.len())))
             } else {
@@ -582,32 +1210,69 @@ pub fn structurally_relate_tys<'tcx, R: TypeRelation<'tcx>>(
         }
 
         (&ty::FnDef(a_def_id, a_args), &ty::FnDef(b_def_id, b_args)) if a_def_id == b_def_id => {
+            trace_rule(relation.tag(), RelateRule::FnDefArgs);
             let args = relation.relate_item_args(a_def_id, a_args, b_args)?;
             Ok(Ty::new_fn_def(tcx, a_def_id, args))
         }
 
         (&ty::FnPtr(a_fty), &ty::FnPtr(b_fty)) => {
+            trace_rule(relation.tag(), RelateRule::FnPtrSig);
             let fty = relation.relate(a_fty, b_fty)?;
             Ok(Ty::new_fn_ptr(tcx, fty))
         }
 
         // Alias tend to mostly already be handled downstream due to normalization.
         (&ty::Alias(a_kind, a_data), &ty::Alias(b_kind, b_data)) => {
-            let alias_ty = relation.relate(a_data, b_data)?;
-            assert_eq!(a_kind, b_kind);
-            Ok(Ty::new_alias(tcx, a_kind, alias_ty))
+            match relation.relate(a_data, b_data) {
+                Ok(alias_ty) => {
+                    assert_eq!(a_kind, b_kind);
+                    Ok(Ty::new_alias(tcx, a_kind, alias_ty))
+                }
+                Err(err) => match relation.structurally_relate_aliases() {
+                    StructurallyRelateAliases::Yes => Err(err),
+                    StructurallyRelateAliases::No => {
+                        relate_normalized_aliases(relation, a, b).unwrap_or(Err(err))
+                    }
+                },
+            }
         }
 
         (&ty::Pat(a_ty, a_pat), &ty::Pat(b_ty, b_pat)) => {
+            trace_rule(relation.tag(), RelateRule::PatBase);
             let ty = relation.relate(a_ty, b_ty)?;
+            trace_rule(relation.tag(), RelateRule::Pattern);
             let pat = relation.relate(a_pat, b_pat)?;
             Ok(Ty::new_pat(tcx, ty, pat))
         }
 
-        _ => Err(TypeError::Sorts(expected_found(a, b))),
+        _ => match relation.structurally_relate_aliases() {
+            StructurallyRelateAliases::Yes => Err(TypeError::Sorts(expected_found(a, b))),
+            StructurallyRelateAliases::No => relate_normalized_aliases(relation, a, b)
+                .unwrap_or_else(|| Err(TypeError::Sorts(expected_found(a, b)))),
+        },
     }
 }
 
+/// Relates `a` and `b` after replacing the aliases among them with their
+/// normalized form, see [`TypeRelation::normalize_alias_hint`]. Returns `None`
+/// if neither of them could be normalized.
+fn relate_normalized_aliases<'tcx, R: TypeRelation<'tcx>>(
+    relation: &mut R,
+    a: Ty<'tcx>,
+    b: Ty<'tcx>,
+) -> Option<RelateResult<'tcx, Ty<'tcx>>> {
+    let mut normalize = |ty: Ty<'tcx>| match *ty.kind() {
+        ty::Alias(_, data) => relation.normalize_alias_hint(data).filter(|&norm| norm != ty),
+        _ => None,
+    };
+    let (a_norm, b_norm) = (normalize(a), normalize(b));
+    if a_norm.is_none() && b_norm.is_none() {
+        return None;
+    }
+    trace_rule(relation.tag(), RelateRule::NormalizedAlias);
+    Some(relation.relate(a_norm.unwrap_or(a), b_norm.unwrap_or(b)))
+}
+
 /// Relates `a` and `b` structurally, calling the relation for all nested values.
 /// Any semantic equality, e.g. of unevaluated consts, and inference variables have
 /// to be handled by the caller.
@@ -621,12 +1286,20 @@ pub fn structurally_relate_consts<'tcx, R: TypeRelation<'tcx>>(
 ) -> RelateResult<'tcx, ty::Const<'tcx>> {
     debug!("{}.structurally_relate_consts(a = {:?}, b = {:?})", relation.tag(), a, b);
     let tcx = relation.tcx();
+    let _depth = RelateDepthGuard::enter(tcx, &(a, b))?;
 
-    if tcx.features().generic_const_exprs {
+    if tcx.features().generic_const_exprs
+        && relation.capabilities().contains(RelateCapabilities::CONST_EVAL)
+    {
         a = tcx.expand_abstract_consts(a);
         b = tcx.expand_abstract_consts(b);
     }
 
+    if tcx.features().generic_const_exprs && relation.normalize_const_exprs() {
+        a = normalize_const_expr(tcx, a);
+        b = normalize_const_expr(tcx, b);
+    }
+
     debug!("{}.structurally_relate_consts(normed_a = {:?}, normed_b = {:?})", relation.tag(), a, b);
 
     // Currently, the values that can be unified are primitive types,
@@ -634,19 +1307,42 @@ pub fn structurally_relate_consts<'tcx, R: TypeRelation<'tcx>>(
     // to structural-match types.
     let is_match = match (a.kind(), b.kind()) {
         (ty::ConstKind::Infer(_), _) | (_, ty::ConstKind::Infer(_)) => {
-            // The caller should handle these cases!
-            bug!("var types encountered in structurally_relate_consts: {:?} {:?}", a, b)
+            if !relation.capabilities().contains(RelateCapabilities::INFER_MISMATCH) {
+                // The caller should handle these cases!
+                bug!("var types encountered in structurally_relate_consts: {:?} {:?}", a, b)
+            }
+            return Err(TypeError::ConstMismatch(expected_found(a, b)));
         }
 
-        (ty::ConstKind::Error(_), _) => return Ok(a),
-        (_, ty::ConstKind::Error(_)) => return Ok(b),
+        (ty::ConstKind::Error(_), _) => {
+            trace_rule(relation.tag(), RelateRule::Error);
+            return Ok(a);
+        }
+        (_, ty::ConstKind::Error(_)) => {
+            trace_rule(relation.tag(), RelateRule::Error);
+            return Ok(b);
+        }
 
         (ty::ConstKind::Param(a_p), ty::ConstKind::Param(b_p)) if a_p.index == b_p.index => {
             debug_assert_eq!(a_p.name, b_p.name, "param types with same index differ in name");
+            trace_rule(relation.tag(), RelateRule::Param);
             true
         }
-        (ty::ConstKind::Placeholder(p1), ty::ConstKind::Placeholder(p2)) => p1 == p2,
-        (ty::ConstKind::Value(_, a_val), ty::ConstKind::Value(_, b_val)) => a_val == b_val,
+        (ty::ConstKind::Placeholder(p1), ty::ConstKind::Placeholder(p2)) => {
+            trace_rule(relation.tag(), RelateRule::Placeholder);
+            p1 == p2
+        }
+        (ty::ConstKind::Value(a_ty, a_val), ty::ConstKind::Value(b_ty, b_val)) => {
+            if a_val == b_val {
+                true
+            } else if a_ty == b_ty {
+                // Point out the part in which two aggregates differ, if any.
+                return Err(valtree_mismatch(tcx, a_ty, a_val, b_val)
+                    .unwrap_or_else(|| TypeError::ConstMismatch(expected_found(a, b))));
+            } else {
+                false
+            }
+        }
 
         // While this is slightly incorrect, it shouldn't matter for `min_const_generics`
         // and is the better alternative to waiting until `generic_const_exprs` can
@@ -658,12 +1354,17 @@ pub fn structurally_relate_consts<'tcx, R: TypeRelation<'tcx>>(
                 assert_eq!(a_ty, b_ty);
             }
 
-            let args = relation.relate_with_variance(
-                ty::Variance::Invariant,
-                ty::VarianceDiagInfo::default(),
-                au.args,
-                bu.args,
-            )?;
+            trace_rule(relation.tag(), RelateRule::UnevaluatedConstArgs);
+            let args = if tcx.def_kind(au.def) == DefKind::InlineConst {
+                relate_inline_const_args(relation, au.def, au.args, bu.args)?
+            } else {
+                relation.relate_with_variance(
+                    ty::Variance::Invariant,
+                    ty::VarianceDiagInfo::default(),
+                    au.args,
+                    bu.args,
+                )?
+            };
             return Ok(ty::Const::new_unevaluated(tcx, ty::UnevaluatedConst { def: au.def, args }));
         }
         (ty::ConstKind::Expr(ae), ty::ConstKind::Expr(be)) => {
@@ -676,6 +1377,7 @@ pub fn structurally_relate_consts<'tcx, R: TypeRelation<'tcx>>(
                 _ => return Err(TypeError::ConstMismatch(expected_found(a, b))),
             }
 
+            trace_rule(relation.tag(), RelateRule::ConstExprArgs);
             let args = relation.relate(ae.args(), be.args())?;
             return Ok(ty::Const::new_expr(tcx, ty::Expr::new(ae.kind, args)));
         }
@@ -684,6 +1386,105 @@ pub fn structurally_relate_consts<'tcx, R: TypeRelation<'tcx>>(
     if is_match { Ok(a) } else { Err(TypeError::ConstMismatch(expected_found(a, b))) }
 }
 
+/// Walks the differing values `a` and `b` of type `ty` in lockstep, returning
+/// a [`TypeError::ConstValueMismatch`] for the first element or field in which
+/// they differ, or `None` if they already differ at the top level.
+///
+/// Strings and byte strings are not descended into, as they are printed as a
+/// whole.
+fn valtree_mismatch<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    mut ty: Ty<'tcx>,
+    mut a: ty::ValTree<'tcx>,
+    mut b: ty::ValTree<'tcx>,
+) -> Option<TypeError<'tcx>> {
+    let mut path = String::new();
+    loop {
+        // References are represented by the value they point to.
+        while let ty::Ref(_, inner, _) = *ty.kind() {
+            ty = inner;
+        }
+        let (ty::ValTree::Branch(a_branches), ty::ValTree::Branch(b_branches)) = (a, b) else {
+            break;
+        };
+        if a_branches.len() != b_branches.len() {
+            break;
+        }
+        let Some(i) = iter::zip(a_branches, b_branches).position(|(a, b)| a != b) else {
+            break;
+        };
+
+        ty = match *ty.kind() {
+            ty::Array(elem_ty, _) | ty::Slice(elem_ty) if elem_ty != tcx.types.u8 => {
+                write!(path, "[{i}]").unwrap();
+                elem_ty
+            }
+            ty::Tuple(tys) => {
+                write!(path, ".{i}").unwrap();
+                tys[i]
+            }
+            ty::Adt(def, args) if def.is_struct() => {
+                let field = &def.non_enum_variant().fields[FieldIdx::from_usize(i)];
+                write!(path, ".{}", field.name).unwrap();
+                field.ty(tcx, args)
+            }
+            // The first branch of an enum is the index of its variant, which
+            // is followed by the fields of that variant.
+            ty::Adt(def, args) if def.is_enum() && i > 0 => {
+                let variant_index = a_branches[0].unwrap_leaf().try_to_u32().ok()?;
+                let variant = def.variant(VariantIdx::from_u32(variant_index));
+                let field = &variant.fields[FieldIdx::from_usize(i - 1)];
+                write!(path, ".{}", field.name).unwrap();
+                field.ty(tcx, args)
+            }
+            _ => break,
+        };
+        (a, b) = (a_branches[i], b_branches[i]);
+    }
+
+    if path.is_empty() {
+        return None;
+    }
+    Some(TypeError::ConstValueMismatch(
+        expected_found(ty::Const::new_value(tcx, a, ty), ty::Const::new_value(tcx, b, ty)),
+        Symbol::intern(&path),
+    ))
+}
+
+/// Relates the args of two uses of the inline const `def_id` invariantly.
+///
+/// Most of these args are inherited from the item enclosing the inline const,
+/// so mismatches in them are reported in terms of the generic parameters of
+/// that item instead of those of the synthetic inline const item.
+fn relate_inline_const_args<'tcx, R: TypeRelation<'tcx>>(
+    relation: &mut R,
+    def_id: DefId,
+    a_args: GenericArgsRef<'tcx>,
+    b_args: GenericArgsRef<'tcx>,
+) -> RelateResult<'tcx, GenericArgsRef<'tcx>> {
+    let tcx = relation.tcx();
+    let generics = tcx.generics_of(def_id);
+    let a = ty::InlineConstArgs { args: a_args };
+    let b = ty::InlineConstArgs { args: b_args };
+
+    let ty = relation
+        .relate_with_variance(ty::Invariant, ty::VarianceDiagInfo::default(), a.ty(), b.ty())
+        .map(GenericArg::from);
+    let parent_args =
+        iter::zip(a.parent_args(), b.parent_args()).enumerate().map(|(i, (&a_arg, &b_arg))| {
+            relation
+                .relate_with_variance(ty::Invariant, ty::VarianceDiagInfo::default(), a_arg, b_arg)
+                .map_err(|err| match err {
+                    TypeError::RelateDepthLimitReached(_) => err,
+                    _ => TypeError::InlineConstParentArgMismatch(
+                        expected_found(a_arg, b_arg),
+                        generics.param_at(i, tcx).def_id,
+                    ),
+                })
+        });
+    tcx.mk_args_from_iter(parent_args.chain(iter::once(ty)))
+}
+
 impl<'tcx> Relate<'tcx> for &'tcx ty::List<ty::PolyExistentialPredicate<'tcx>> {
     fn relate<R: TypeRelation<'tcx>>(
         relation: &mut R,
@@ -691,44 +1492,90 @@ fn relate<R: TypeRelation<'tcx>>(
         b: Self,
     ) -> RelateResult<'tcx, Self> {
         let tcx = relation.tcx();
+        let _timer = ExistentialPredicatesTimer::start(tcx);
 
-        // FIXME: this is wasteful, but want to do a perf run to see how slow it is.
-        // We need to perform this deduplication as we sometimes generate duplicate projections
-        // in `a`.
-        let mut a_v: Vec<_> = a.into_iter().collect();
-        let mut b_v: Vec<_> = b.into_iter().collect();
+        // Interned lists are sorted by `stable_cmp` and deduplicated, see
+        // `TyCtxt::mk_poly_existential_predicates`, so corresponding predicates
+        // are at the same index of both lists.
         // `skip_binder` here is okay because `stable_cmp` doesn't look at binders
-        a_v.sort_by(|a, b| a.skip_binder().stable_cmp(tcx, &b.skip_binder()));
-        a_v.dedup();
-        b_v.sort_by(|a, b| a.skip_binder().stable_cmp(tcx, &b.skip_binder()));
-        b_v.dedup();
-        if a_v.len() != b_v.len() {
-            return Err(TypeError::ExistentialMismatch(expected_found(a, b)));
-        }
-
-        let v = iter::zip(a_v, b_v).map(|(ep_a, ep_b)| {
-            match (ep_a.skip_binder(), ep_b.skip_binder()) {
-                (ExistentialPredicate::Trait(a), ExistentialPredicate::Trait(b)) => Ok(ep_a
-                    .rebind(ExistentialPredicate::Trait(
+        let aligned = a.len() == b.len()
+            && iter::zip(a, b).all(|(ep_a, ep_b)| {
+                ep_a.skip_binder().stable_cmp(tcx, &ep_b.skip_binder()) == Ordering::Equal
+            });
+        if !aligned {
+            return Err(unaligned_existential_predicates_error(tcx, a, b));
+        }
+
+        let v = iter::zip(a, b).enumerate().map(|(i, (ep_a, ep_b))| {
+            let ep = match (ep_a.skip_binder(), ep_b.skip_binder()) {
+                (ExistentialPredicate::Trait(a), ExistentialPredicate::Trait(b)) => {
+                    trace_rule(relation.tag(), RelateRule::DynPrincipal);
+                    ExistentialPredicate::Trait(
                         relation.relate(ep_a.rebind(a), ep_b.rebind(b))?.skip_binder(),
-                    ))),
+                    )
+                }
                 (ExistentialPredicate::Projection(a), ExistentialPredicate::Projection(b)) => {
-                    Ok(ep_a.rebind(ExistentialPredicate::Projection(
+                    trace_rule(relation.tag(), RelateRule::DynProjection(i));
+                    ExistentialPredicate::Projection(
                         relation.relate(ep_a.rebind(a), ep_b.rebind(b))?.skip_binder(),
-                    )))
+                    )
                 }
-                (ExistentialPredicate::AutoTrait(a), ExistentialPredicate::AutoTrait(b))
-                    if a == b =>
-                {
-                    Ok(ep_a.rebind(ExistentialPredicate::AutoTrait(a)))
+                (ExistentialPredicate::AutoTrait(a), ExistentialPredicate::AutoTrait(_)) => {
+                    trace_rule(relation.tag(), RelateRule::DynAutoTrait);
+                    ExistentialPredicate::AutoTrait(a)
                 }
-                _ => Err(TypeError::ExistentialMismatch(expected_found(a, b))),
-            }
+                _ => bug!("existential predicates of different kinds compare equal"),
+            };
+            Ok(ep_a.rebind(ep))
         });
         tcx.mk_poly_existential_predicates_from_iter(v)
     }
 }
 
+/// Returns the error for two lists of existential predicates whose predicates
+/// don't correspond to each other. If the first predicate which is only present
+/// in one of them is an auto trait, the error names the auto traits in which
+/// the lists differ.
+#[cold]
+fn unaligned_existential_predicates_error<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    a: &'tcx ty::List<ty::PolyExistentialPredicate<'tcx>>,
+    b: &'tcx ty::List<ty::PolyExistentialPredicate<'tcx>>,
+) -> TypeError<'tcx> {
+    // Both lists are sorted by the same key, so walk them in lockstep until
+    // one of them has a predicate the other one lacks.
+    let (mut a_iter, mut b_iter) = (a.iter().peekable(), b.iter().peekable());
+    let (missing, in_a) = loop {
+        match (a_iter.peek(), b_iter.peek()) {
+            (Some(&ep_a), Some(&ep_b)) => {
+                match ep_a.skip_binder().stable_cmp(tcx, &ep_b.skip_binder()) {
+                    Ordering::Less => break (ep_a, true),
+                    Ordering::Greater => break (ep_b, false),
+                    Ordering::Equal => {
+                        a_iter.next();
+                        b_iter.next();
+                    }
+                }
+            }
+            (Some(&ep_a), None) => break (ep_a, true),
+            (None, Some(&ep_b)) => break (ep_b, false),
+            (None, None) => bug!("existential predicates {a:?} and {b:?} are aligned"),
+        }
+    };
+
+    match missing.skip_binder() {
+        ExistentialPredicate::AutoTrait(_) => {
+            let (a_autos, b_autos): (Vec<_>, Vec<_>) =
+                (a.auto_traits().collect(), b.auto_traits().collect());
+            let a_only = a_autos.iter().copied().find(|def_id| !b_autos.contains(def_id));
+            let b_only = b_autos.iter().copied().find(|def_id| !a_autos.contains(def_id));
+            debug_assert!(if in_a { a_only.is_some() } else { b_only.is_some() });
+            TypeError::MissingAutoTrait(expected_found(a_only, b_only))
+        }
+        _ => TypeError::ExistentialMismatch(expected_found(a, b)),
+    }
+}
+
 impl<'tcx> Relate<'tcx> for GenericArgsRef<'tcx> {
     fn relate<R: TypeRelation<'tcx>>(
         relation: &mut R,
@@ -785,15 +1632,8 @@ fn relate<R: TypeRelation<'tcx>>(
             (GenericArgKind::Const(a_ct), GenericArgKind::Const(b_ct)) => {
                 Ok(relation.relate(a_ct, b_ct)?.into())
             }
-            (GenericArgKind::Lifetime(unpacked), x) => {
-                bug!("impossible case reached: can't relate: {:?} with {:?}", unpacked, x)
-            }
-            (GenericArgKind::Type(unpacked), x) => {
-                bug!("impossible case reached: can't relate: {:?} with {:?}", unpacked, x)
-            }
-            (GenericArgKind::Const(unpacked), x) => {
-                bug!("impossible case reached: can't relate: {:?} with {:?}", unpacked, x)
-            }
+            _ => Err(GenericArgKindMismatch { owner: None, index: 0, a_arg: &[a], b_arg: &[b] }
+                .report(relation.tcx())),
         }
     }
 }
@@ -821,6 +1661,109 @@ fn relate<R: TypeRelation<'tcx>>(
     }
 }
 
+impl<'tcx, T: Relate<'tcx>> Relate<'tcx> for ty::OutlivesPredicate<'tcx, T> {
+    fn relate<R: TypeRelation<'tcx>>(
+        relation: &mut R,
+        a: ty::OutlivesPredicate<'tcx, T>,
+        b: ty::OutlivesPredicate<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::OutlivesPredicate<'tcx, T>> {
+        let value = relation.relate_with_variance(
+            ty::Invariant,
+            ty::VarianceDiagInfo::default(),
+            a.0,
+            b.0,
+        )?;
+        let region = relation.relate_with_variance(
+            ty::Invariant,
+            ty::VarianceDiagInfo::default(),
+            a.1,
+            b.1,
+        )?;
+        Ok(ty::OutlivesPredicate(value, region))
+    }
+}
+
+impl<'tcx> Relate<'tcx> for ty::ProjectionPredicate<'tcx> {
+    fn relate<R: TypeRelation<'tcx>>(
+        relation: &mut R,
+        a: ty::ProjectionPredicate<'tcx>,
+        b: ty::ProjectionPredicate<'tcx>,
+    ) -> RelateResult<'tcx, ty::ProjectionPredicate<'tcx>> {
+        let projection_term = relation.relate(a.projection_term, b.projection_term)?;
+        let term = relation.relate_with_variance(
+            ty::Invariant,
+            ty::VarianceDiagInfo::default(),
+            a.term,
+            b.term,
+        )?;
+        Ok(ty::ProjectionPredicate { projection_term, term })
+    }
+}
+
+impl<'tcx> Relate<'tcx> for ty::NormalizesTo<'tcx> {
+    fn relate<R: TypeRelation<'tcx>>(
+        relation: &mut R,
+        a: ty::NormalizesTo<'tcx>,
+        b: ty::NormalizesTo<'tcx>,
+    ) -> RelateResult<'tcx, ty::NormalizesTo<'tcx>> {
+        let alias = relation.relate(a.alias, b.alias)?;
+        let term = relation.relate_with_variance(
+            ty::Invariant,
+            ty::VarianceDiagInfo::default(),
+            a.term,
+            b.term,
+        )?;
+        Ok(ty::NormalizesTo { alias, term })
+    }
+}
+
+impl<'tcx> Relate<'tcx> for ty::SubtypePredicate<'tcx> {
+    fn relate<R: TypeRelation<'tcx>>(
+        relation: &mut R,
+        a: ty::SubtypePredicate<'tcx>,
+        b: ty::SubtypePredicate<'tcx>,
+    ) -> RelateResult<'tcx, ty::SubtypePredicate<'tcx>> {
+        if a.a_is_expected != b.a_is_expected {
+            return Err(TypeError::Mismatch);
+        }
+        let a_ty = relation.relate_with_variance(
+            ty::Invariant,
+            ty::VarianceDiagInfo::default(),
+            a.a,
+            b.a,
+        )?;
+        let b_ty = relation.relate_with_variance(
+            ty::Invariant,
+            ty::VarianceDiagInfo::default(),
+            a.b,
+            b.b,
+        )?;
+        Ok(ty::SubtypePredicate { a_is_expected: a.a_is_expected, a: a_ty, b: b_ty })
+    }
+}
+
+impl<'tcx> Relate<'tcx> for ty::CoercePredicate<'tcx> {
+    fn relate<R: TypeRelation<'tcx>>(
+        relation: &mut R,
+        a: ty::CoercePredicate<'tcx>,
+        b: ty::CoercePredicate<'tcx>,
+    ) -> RelateResult<'tcx, ty::CoercePredicate<'tcx>> {
+        let a_ty = relation.relate_with_variance(
+            ty::Invariant,
+            ty::VarianceDiagInfo::default(),
+            a.a,
+            b.a,
+        )?;
+        let b_ty = relation.relate_with_variance(
+            ty::Invariant,
+            ty::VarianceDiagInfo::default(),
+            a.b,
+            b.b,
+        )?;
+        Ok(ty::CoercePredicate { a: a_ty, b: b_ty })
+    }
+}
+
 impl<'tcx> Relate<'tcx> for Term<'tcx> {
     fn relate<R: TypeRelation<'tcx>>(
         relation: &mut R,
@@ -830,11 +1773,113 @@ fn relate<R: TypeRelation<'tcx>>(
         Ok(match (a.unpack(), b.unpack()) {
             (TermKind::Ty(a), TermKind::Ty(b)) => relation.relate(a, b)?.into(),
             (TermKind::Const(a), TermKind::Const(b)) => relation.relate(a, b)?.into(),
-            _ => return Err(TypeError::Mismatch),
+            _ => return Err(TypeError::TermKindMismatch(expected_found(a, b))),
         })
     }
 }
 
+impl<'tcx> Relate<'tcx> for ty::ClauseKind<'tcx> {
+    fn relate<R: TypeRelation<'tcx>>(
+        relation: &mut R,
+        a: Self,
+        b: Self,
+    ) -> RelateResult<'tcx, Self> {
+        use ty::ClauseKind::*;
+        Ok(match (a, b) {
+            (Trait(a), Trait(b)) => Trait(relation.relate(a, b)?),
+            (RegionOutlives(a), RegionOutlives(b)) => RegionOutlives(relation.relate(a, b)?),
+            (TypeOutlives(a), TypeOutlives(b)) => TypeOutlives(relation.relate(a, b)?),
+            (Projection(a), Projection(b)) => Projection(relation.relate(a, b)?),
+            (ConstArgHasType(a_ct, a_ty), ConstArgHasType(b_ct, b_ty)) => {
+                ConstArgHasType(relation.relate(a_ct, b_ct)?, relation.relate(a_ty, b_ty)?)
+            }
+            (WellFormed(a), WellFormed(b)) => WellFormed(relation.relate(a, b)?),
+            (ConstEvaluatable(a), ConstEvaluatable(b)) => {
+                ConstEvaluatable(relation.relate(a, b)?)
+            }
+            _ => {
+                let (a, b) = (ty::PredicateKind::Clause(a), ty::PredicateKind::Clause(b));
+                return Err(TypeError::PredicateKindMismatch(expected_found(a, b)));
+            }
+        })
+    }
+}
+
+impl<'tcx> Relate<'tcx> for ty::PredicateKind<'tcx> {
+    fn relate<R: TypeRelation<'tcx>>(
+        relation: &mut R,
+        a: Self,
+        b: Self,
+    ) -> RelateResult<'tcx, Self> {
+        use ty::PredicateKind::*;
+        Ok(match (a, b) {
+            (Clause(a), Clause(b)) => Clause(relation.relate(a, b)?),
+            (ObjectSafe(a), ObjectSafe(b)) => {
+                if a != b {
+                    return Err(TypeError::Traits(expected_found(a, b)));
+                }
+                ObjectSafe(a)
+            }
+            (Subtype(a), Subtype(b)) => Subtype(relation.relate(a, b)?),
+            (Coerce(a), Coerce(b)) => Coerce(relation.relate(a, b)?),
+            (ConstEquate(a_lhs, a_rhs), ConstEquate(b_lhs, b_rhs)) => {
+                ConstEquate(relation.relate(a_lhs, b_lhs)?, relation.relate(a_rhs, b_rhs)?)
+            }
+            (Ambiguous, Ambiguous) => Ambiguous,
+            (NormalizesTo(a), NormalizesTo(b)) => NormalizesTo(relation.relate(a, b)?),
+            (AliasRelate(a_lhs, a_rhs, a_dir), AliasRelate(b_lhs, b_rhs, b_dir)) => {
+                if a_dir != b_dir {
+                    return Err(TypeError::Mismatch);
+                }
+                AliasRelate(relation.relate(a_lhs, b_lhs)?, relation.relate(a_rhs, b_rhs)?, a_dir)
+            }
+            _ => return Err(TypeError::PredicateKindMismatch(expected_found(a, b))),
+        })
+    }
+}
+
+/// Whether `a` and `b` are the same kind of predicate, e.g. both trait
+/// predicates, and can therefore be related.
+fn same_predicate_kind<'tcx>(a: ty::Predicate<'tcx>, b: ty::Predicate<'tcx>) -> bool {
+    match (a.kind().skip_binder(), b.kind().skip_binder()) {
+        (ty::PredicateKind::Clause(a), ty::PredicateKind::Clause(b)) => {
+            std::mem::discriminant(&a) == std::mem::discriminant(&b)
+        }
+        (a, b) => std::mem::discriminant(&a) == std::mem::discriminant(&b),
+    }
+}
+
+impl<'tcx> Relate<'tcx> for ty::Predicate<'tcx> {
+    fn relate<R: TypeRelation<'tcx>>(
+        relation: &mut R,
+        a: Self,
+        b: Self,
+    ) -> RelateResult<'tcx, Self> {
+        if !same_predicate_kind(a, b) {
+            let (a, b) = (a.kind().skip_binder(), b.kind().skip_binder());
+            return Err(TypeError::PredicateKindMismatch(expected_found(a, b)));
+        }
+        let kind = relation.relate(a.kind(), b.kind())?;
+        Ok(kind.upcast(relation.tcx()))
+    }
+}
+
+impl<'tcx> Relate<'tcx> for ty::Clause<'tcx> {
+    fn relate<R: TypeRelation<'tcx>>(
+        relation: &mut R,
+        a: Self,
+        b: Self,
+    ) -> RelateResult<'tcx, Self> {
+        if !same_predicate_kind(a.as_predicate(), b.as_predicate()) {
+            let (a, b) = (a.as_predicate().kind(), b.as_predicate().kind());
+            let (a, b) = (a.skip_binder(), b.skip_binder());
+            return Err(TypeError::PredicateKindMismatch(expected_found(a, b)));
+        }
+        let kind = relation.relate(a.kind(), b.kind())?;
+        Ok(kind.upcast(relation.tcx()))
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////
 // Error handling
 
@@ -0,0 +1,131 @@
+//! Comparing types for API compatibility.
+//!
+//! Tools which check whether a change to a crate is semver-compatible compare
+//! the types in its public API before and after the change. Adding a generic
+//! parameter with a default, e.g. turning `Vec<T>` into `Vec<T, A = Global>`,
+//! is compatible, as all existing uses of the type are still valid. Relating
+//! the two types with [`api_types_compatible`] treats the missing argument of
+//! the old type as being equal to the default of the new one.
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::{self, Relate, RelateResult, TypeRelation};
+use crate::ty::{self, GenericArg, GenericArgKind, GenericArgsRef, Ty, TyCtxt};
+use rustc_hir::def_id::DefId;
+
+/// Returns an error if `a` and `b` are not the same type, ignoring trailing
+/// generic arguments which are only present on one side and are equal to the
+/// default of their parameter.
+pub fn api_types_compatible<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    a: Ty<'tcx>,
+    b: Ty<'tcx>,
+) -> RelateResult<'tcx, ()> {
+    if a == b {
+        return Ok(());
+    }
+
+    IgnoreDefaultedArgs::new(tcx).relate(a, b)?;
+    Ok(())
+}
+
+/// A relation which relates values if they are structurally equal, except
+/// that generic args which are filled in from the default of their parameter
+/// may be missing on either side. See the module docs.
+pub struct IgnoreDefaultedArgs<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> IgnoreDefaultedArgs<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> IgnoreDefaultedArgs<'tcx> {
+        IgnoreDefaultedArgs { tcx }
+    }
+}
+
+impl<'tcx> TypeRelation<'tcx> for IgnoreDefaultedArgs<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "IgnoreDefaultedArgs"
+    }
+
+    fn relate_item_args(
+        &mut self,
+        item_def_id: DefId,
+        a_args: GenericArgsRef<'tcx>,
+        b_args: GenericArgsRef<'tcx>,
+    ) -> RelateResult<'tcx, GenericArgsRef<'tcx>> {
+        let (shorter, longer) =
+            if a_args.len() <= b_args.len() { (a_args, b_args) } else { (b_args, a_args) };
+        let args = relate::relate_args_invariantly(self, a_args, b_args)?;
+        if shorter.len() == longer.len() {
+            return Ok(args);
+        }
+
+        let tcx = self.tcx;
+        let generics = tcx.generics_of(item_def_id);
+        for (i, &arg) in longer.iter().enumerate().skip(shorter.len()) {
+            let param = generics.param_at(i, tcx);
+            let default = param.default_value(tcx).map(|default| default.instantiate(tcx, longer));
+            if default != Some(arg) {
+                return Err(missing_arg_error(longer == a_args, default, arg));
+            }
+        }
+        Ok(tcx.mk_args_from_iter(args.iter().chain(longer.iter().skip(shorter.len()))))
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        _: ty::Variance,
+        _: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        self.relate(a, b)
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        if a == b {
+            return Ok(a);
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::Infer(_) | ty::Bound(..), _) | (_, ty::Infer(_) | ty::Bound(..)) => {
+                Err(TypeError::Sorts(relate::expected_found(a, b)))
+            }
+            _ => relate::structurally_relate_tys(self, a, b),
+        }
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        relate::relate_higher_ranked(self, a, b)
+    }
+}
+
+/// The error for an argument `arg` without a counterpart on the other side
+/// which isn't equal to the `default` of its parameter.
+fn missing_arg_error<'tcx>(
+    arg_is_expected: bool,
+    default: Option<GenericArg<'tcx>>,
+    arg: GenericArg<'tcx>,
+) -> TypeError<'tcx> {
+    match (default.map(GenericArg::unpack), arg.unpack()) {
+        (Some(GenericArgKind::Type(default)), GenericArgKind::Type(arg)) => {
+            let (a, b) = if arg_is_expected { (arg, default) } else { (default, arg) };
+            TypeError::Sorts(relate::expected_found(a, b))
+        }
+        (Some(GenericArgKind::Const(default)), GenericArgKind::Const(arg)) => {
+            let (a, b) = if arg_is_expected { (arg, default) } else { (default, arg) };
+            TypeError::ConstMismatch(relate::expected_found(a, b))
+        }
+        _ => TypeError::Mismatch,
+    }
+}
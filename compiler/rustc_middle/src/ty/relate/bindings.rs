@@ -0,0 +1,308 @@
+//! Recording which holes of a template were bound while relating it.
+//!
+//! Callers which relate a generic template, e.g. the trait ref of an impl
+//! instantiated with fresh inference variables, against a concrete value
+//! often want to know what each parameter or inference variable of the
+//! template corresponds to. Instead of walking both values again after
+//! relating them, they can wrap their relation in [`RecordBindings`].
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::{
+    self, Relate, RelateCapabilities, RelateResult, StructurallyRelateAliases, TypeRelation,
+};
+use crate::ty::{self, GenericArg, GenericArgsRef, Ty, TyCtxt};
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_target::spec::abi;
+
+/// The values the holes of a template have been bound to, in the order in
+/// which they were first encountered.
+#[derive(Clone, Debug, Default)]
+pub struct Bindings<'tcx> {
+    map: FxIndexMap<GenericArg<'tcx>, GenericArg<'tcx>>,
+}
+
+impl<'tcx> Bindings<'tcx> {
+    /// Returns the value `hole` has been bound to, if any.
+    pub fn get(&self, hole: impl Into<GenericArg<'tcx>>) -> Option<GenericArg<'tcx>> {
+        self.map.get(&hole.into()).copied()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (GenericArg<'tcx>, GenericArg<'tcx>)> + '_ {
+        self.map.iter().map(|(&hole, &value)| (hole, value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Binds `hole` to `value`, failing if it has already been bound to a
+    /// different value.
+    pub(super) fn bind(
+        &mut self,
+        hole: GenericArg<'tcx>,
+        value: GenericArg<'tcx>,
+    ) -> Result<(), ()> {
+        let existing = *self.map.entry(hole).or_insert(value);
+        if existing == value { Ok(()) } else { Err(()) }
+    }
+}
+
+/// Wraps a relation, recording what the holes in `a`, i.e. type, const, and
+/// early-bound region parameters as well as inference variables, are related
+/// to in `b`.
+///
+/// All values are related by the wrapped relation, so its variance, alias
+/// handling and obligations are preserved. Once it succeeds, the related
+/// values are walked in lockstep to record the holes nested in them. A hole
+/// which is related to two different values results in an error.
+pub struct RecordBindings<'tcx, R> {
+    relation: R,
+    bindings: Bindings<'tcx>,
+}
+
+impl<'tcx, R: TypeRelation<'tcx>> RecordBindings<'tcx, R> {
+    pub fn new(relation: R) -> RecordBindings<'tcx, R> {
+        RecordBindings { relation, bindings: Bindings::default() }
+    }
+
+    /// Relates `a` and `b`, returning the related value together with the
+    /// values the holes of `a` were bound to.
+    pub fn relate_and_record<T: Relate<'tcx>>(
+        mut self,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, (T, Bindings<'tcx>)> {
+        let value = self.relate(a, b)?;
+        Ok((value, self.bindings))
+    }
+
+    /// Returns the wrapped relation and the bindings recorded so far.
+    pub fn into_parts(self) -> (R, Bindings<'tcx>) {
+        (self.relation, self.bindings)
+    }
+
+    /// Records the holes in `a`, which the wrapped relation successfully
+    /// related to `b`, returning `value`.
+    fn record<T: Relate<'tcx>, U>(&mut self, a: T, b: T, value: U) -> RelateResult<'tcx, U> {
+        let mut collector =
+            CollectHoles { tcx: self.relation.tcx(), bindings: &mut self.bindings, conflict: None };
+        let _ = collector.relate(a, b);
+        match collector.conflict {
+            Some(err) => Err(err),
+            None => Ok(value),
+        }
+    }
+}
+
+impl<'tcx, R: TypeRelation<'tcx>> TypeRelation<'tcx> for RecordBindings<'tcx, R> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.relation.tcx()
+    }
+
+    fn tag(&self) -> &'static str {
+        self.relation.tag()
+    }
+
+    fn relate_item_args(
+        &mut self,
+        item_def_id: DefId,
+        a_arg: GenericArgsRef<'tcx>,
+        b_arg: GenericArgsRef<'tcx>,
+    ) -> RelateResult<'tcx, GenericArgsRef<'tcx>> {
+        let args = self.relation.relate_item_args(item_def_id, a_arg, b_arg)?;
+        self.record(a_arg, b_arg, args)
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        variance: ty::Variance,
+        info: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        let value = self.relation.relate_with_variance(variance, info, a, b)?;
+        self.record(a, b, value)
+    }
+
+    fn relate_fresh_tys_by_index(&self) -> bool {
+        self.relation.relate_fresh_tys_by_index()
+    }
+
+    fn normalize_alias_hint(&mut self, alias: ty::AliasTy<'tcx>) -> Option<Ty<'tcx>> {
+        self.relation.normalize_alias_hint(alias)
+    }
+
+    fn structurally_relate_aliases(&self) -> StructurallyRelateAliases {
+        self.relation.structurally_relate_aliases()
+    }
+
+    fn normalize_const_exprs(&self) -> bool {
+        self.relation.normalize_const_exprs()
+    }
+
+    fn collect_errors(&mut self) -> Option<&mut Vec<TypeError<'tcx>>> {
+        self.relation.collect_errors()
+    }
+
+    fn capabilities(&self) -> RelateCapabilities {
+        self.relation.capabilities()
+    }
+
+    fn can_shortcircuit_on_equality(&self) -> bool {
+        // Parameters have to be bound even if they are related to themselves.
+        false
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        let ty = self.relation.tys(a, b)?;
+        self.record(a, b, ty)
+    }
+
+    fn regions(
+        &mut self,
+        a: ty::Region<'tcx>,
+        b: ty::Region<'tcx>,
+    ) -> RelateResult<'tcx, ty::Region<'tcx>> {
+        let r = self.relation.regions(a, b)?;
+        self.record(a, b, r)
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        let ct = self.relation.consts(a, b)?;
+        self.record(a, b, ct)
+    }
+
+    fn safeties(&mut self, a: hir::Safety, b: hir::Safety) -> RelateResult<'tcx, hir::Safety> {
+        self.relation.safeties(a, b)
+    }
+
+    fn abis(&mut self, a: abi::Abi, b: abi::Abi) -> RelateResult<'tcx, abi::Abi> {
+        self.relation.abis(a, b)
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        let value = self.relation.binders(a, b)?;
+        self.record(a, b, value)
+    }
+}
+
+/// Walks two values which have already been related in lockstep, binding the
+/// holes of `a` to the corresponding values of `b`, see [`RecordBindings`].
+///
+/// Components whose structure differs, e.g. because the wrapped relation
+/// normalized an alias, are skipped. The first hole bound to two different
+/// values is stored in `conflict`.
+struct CollectHoles<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    bindings: &'a mut Bindings<'tcx>,
+    conflict: Option<TypeError<'tcx>>,
+}
+
+impl<'tcx> CollectHoles<'_, 'tcx> {
+    fn bind(&mut self, hole: GenericArg<'tcx>, value: GenericArg<'tcx>, err: TypeError<'tcx>) {
+        if self.bindings.bind(hole, value).is_err() && self.conflict.is_none() {
+            self.conflict = Some(err);
+        }
+    }
+}
+
+impl<'tcx> TypeRelation<'tcx> for CollectHoles<'_, 'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "CollectHoles"
+    }
+
+    fn capabilities(&self) -> RelateCapabilities {
+        RelateCapabilities::empty()
+    }
+
+    fn can_shortcircuit_on_equality(&self) -> bool {
+        false
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        _: ty::Variance,
+        _: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        self.relate(a, b)
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        match (a.kind(), b.kind()) {
+            (ty::Param(_) | ty::Infer(_), _) => {
+                self.bind(a.into(), b.into(), TypeError::Sorts(relate::expected_found(a, b)));
+            }
+            (_, ty::Infer(_)) => {}
+            _ => {
+                let _ = relate::structurally_relate_tys(self, a, b);
+            }
+        }
+        Ok(a)
+    }
+
+    fn regions(
+        &mut self,
+        a: ty::Region<'tcx>,
+        b: ty::Region<'tcx>,
+    ) -> RelateResult<'tcx, ty::Region<'tcx>> {
+        if let ty::ReEarlyParam(_) | ty::ReVar(_) = a.kind() {
+            self.bind(a.into(), b.into(), TypeError::Mismatch);
+        }
+        Ok(a)
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        match (a.kind(), b.kind()) {
+            (ty::ConstKind::Param(_) | ty::ConstKind::Infer(_), _) => {
+                self.bind(
+                    a.into(),
+                    b.into(),
+                    TypeError::ConstMismatch(relate::expected_found(a, b)),
+                );
+            }
+            (_, ty::ConstKind::Infer(_)) => {}
+            _ => {
+                let _ = relate::structurally_relate_consts(self, a, b);
+            }
+        }
+        Ok(a)
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        Ok(a.rebind(self.relate(a.skip_binder(), b.skip_binder())?))
+    }
+}
@@ -0,0 +1,89 @@
+//! Memoizing the results of structurally relating values.
+//!
+//! Relating large types, e.g. deeply nested ADTs with many generic args,
+//! often ends up relating the same pairs of types over and over. Relations
+//! whose results only depend on the values being related can opt into a
+//! [`RelateCache`], see [`TypeRelation::cache`], so that repeated subtrees are
+//! only walked once.
+
+use std::hash::Hash;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::DefId;
+use tracing::debug;
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::{RelateResult, TypeRelation};
+use crate::ty::{self, GenericArgsRef, Ty};
+
+type CacheMap<'tcx, K, V> = FxHashMap<(ty::Variance, K), RelateResult<'tcx, V>>;
+
+/// The results of relating values, keyed by the ambient variance and the
+/// values themselves.
+#[derive(Default)]
+pub struct RelateCache<'tcx> {
+    tys: CacheMap<'tcx, (Ty<'tcx>, Ty<'tcx>), Ty<'tcx>>,
+    item_args:
+        CacheMap<'tcx, (DefId, GenericArgsRef<'tcx>, GenericArgsRef<'tcx>), GenericArgsRef<'tcx>>,
+}
+
+impl<'tcx> RelateCache<'tcx> {
+    pub fn new() -> RelateCache<'tcx> {
+        RelateCache::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.tys.clear();
+        self.item_args.clear();
+    }
+}
+
+/// Relates `a` and `b` using `relate`, consulting the cache of `relation`, if
+/// any, first.
+fn cached<'tcx, R, K, V>(
+    relation: &mut R,
+    map: fn(&mut RelateCache<'tcx>) -> &mut CacheMap<'tcx, K, V>,
+    key: K,
+    relate: impl FnOnce(&mut R) -> RelateResult<'tcx, V>,
+) -> RelateResult<'tcx, V>
+where
+    R: TypeRelation<'tcx>,
+    K: Copy + Eq + Hash + std::fmt::Debug,
+    V: Copy,
+{
+    let Some((variance, cache)) = relation.cache() else {
+        return relate(relation);
+    };
+    if let Some(&result) = map(cache).get(&(variance, key)) {
+        debug!(relation = relation.tag(), ?variance, ?key, "relate cache hit");
+        return result;
+    }
+
+    let result = relate(relation);
+    // Hitting the depth limit depends on how deeply nested the values are
+    // being related, not just on the values themselves.
+    if !matches!(result, Err(TypeError::RelateDepthLimitReached(_))) {
+        let (_, cache) = relation.cache().unwrap();
+        map(cache).insert((variance, key), result);
+    }
+    result
+}
+
+pub(super) fn cached_tys<'tcx, R: TypeRelation<'tcx>>(
+    relation: &mut R,
+    a: Ty<'tcx>,
+    b: Ty<'tcx>,
+    relate: impl FnOnce(&mut R) -> RelateResult<'tcx, Ty<'tcx>>,
+) -> RelateResult<'tcx, Ty<'tcx>> {
+    cached(relation, |cache| &mut cache.tys, (a, b), relate)
+}
+
+pub(super) fn cached_item_args<'tcx, R: TypeRelation<'tcx>>(
+    relation: &mut R,
+    item_def_id: DefId,
+    a_args: GenericArgsRef<'tcx>,
+    b_args: GenericArgsRef<'tcx>,
+    relate: impl FnOnce(&mut R) -> RelateResult<'tcx, GenericArgsRef<'tcx>>,
+) -> RelateResult<'tcx, GenericArgsRef<'tcx>> {
+    cached(relation, |cache| &mut cache.item_args, (item_def_id, a_args, b_args), relate)
+}
@@ -0,0 +1,44 @@
+//! What a relation expects from the structural relation code.
+//!
+//! The structural functions, e.g. [`structurally_relate_tys`], are shared by
+//! all relations, from type inference to diagnostics-only comparisons. Instead
+//! of adding a boolean parameter or a new function variant whenever a relation
+//! needs them to behave slightly differently, each relation declares its
+//! [`RelateCapabilities`] and the structural functions consult them.
+//!
+//! [`structurally_relate_tys`]: super::structurally_relate_tys
+
+/// The capabilities of a relation, see
+/// [`TypeRelation::capabilities`](super::TypeRelation::capabilities).
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RelateCapabilities(u8);
+bitflags::bitflags! {
+    impl RelateCapabilities: u8 {
+        /// Inference variables which reach the structural functions are a
+        /// mismatch. Without this capability, the relation is expected to
+        /// handle them itself, so reaching one of them is a bug.
+        const INFER_MISMATCH    = 1 << 0;
+        /// The relation wants the structural functions to compute additional
+        /// information which is only used for diagnostics, such as the type
+        /// in [`ty::VarianceDiagInfo::Invariant`](crate::ty::VarianceDiagInfo).
+        const WANTS_DIAGNOSTICS = 1 << 1;
+        /// Regions are significant for the relation. Without this capability,
+        /// the default [`TypeRelation::regions`](super::TypeRelation::regions)
+        /// relates all regions.
+        const REGION_SENSITIVE  = 1 << 2;
+        /// The structural functions may run queries which evaluate or expand
+        /// constants, e.g. to expand abstract consts under `generic_const_exprs`.
+        const CONST_EVAL        = 1 << 3;
+    }
+}
+rustc_data_structures::external_bitflags_debug! { RelateCapabilities }
+
+impl Default for RelateCapabilities {
+    /// The capabilities of a relation outside of type inference which cares
+    /// about regions.
+    fn default() -> Self {
+        RelateCapabilities::WANTS_DIAGNOSTICS
+            | RelateCapabilities::REGION_SENSITIVE
+            | RelateCapabilities::CONST_EVAL
+    }
+}
@@ -0,0 +1,223 @@
+//! Normalizing const expressions modulo simple algebraic identities.
+//!
+//! Under `generic_const_exprs`, const arguments like `N + 1` are represented
+//! as [`ty::Expr`] trees, which are related structurally, so `N + 1` and
+//! `1 + N` don't unify even though they are obviously equal. Relations which
+//! opt into [`TypeRelation::normalize_const_exprs`] relate the trees returned
+//! by [`normalize_const_expr`] instead.
+//!
+//! [`TypeRelation::normalize_const_exprs`]: super::TypeRelation::normalize_const_exprs
+
+use crate::mir::{BinOp, UnOp};
+use crate::ty::{self, GenericArgKind, Ty, TyCtxt};
+
+/// Returns `ct` with all of its const expressions normalized, so that
+/// expressions which are equal modulo the following identities are equal:
+///
+/// - the operands of commutative operations are in a canonical order, with
+///   parameters first and literals last, e.g. `1 + N` becomes `N + 1`,
+/// - operations whose operands are all literals are folded, unless they
+///   would overflow, e.g. `N * (2 + 3)` becomes `N * 5`,
+/// - nested associative operations are flattened, which also allows folding
+///   their literal operands, e.g. `(N + 1) + (M + 2)` becomes `N + M + 3`.
+///
+/// Only the bitwise operations and the addition of unsigned integers are
+/// treated as associative, as regrouping any other operation may change
+/// whether it overflows.
+pub fn normalize_const_expr<'tcx>(tcx: TyCtxt<'tcx>, ct: ty::Const<'tcx>) -> ty::Const<'tcx> {
+    let ty::ConstKind::Expr(expr) = ct.kind() else {
+        return ct;
+    };
+
+    match expr.kind {
+        ty::ExprKind::Binop(op) => {
+            let (lhs_ty, rhs_ty, lhs, rhs) = expr.binop_args();
+            let (lhs, rhs) = (normalize_const_expr(tcx, lhs), normalize_const_expr(tcx, rhs));
+            if lhs_ty == rhs_ty && is_associative(op, lhs_ty) {
+                let mut operands = vec![];
+                flatten(op, lhs_ty, lhs, &mut operands);
+                flatten(op, lhs_ty, rhs, &mut operands);
+                let (literals, mut operands): (Vec<_>, Vec<_>) = operands
+                    .into_iter()
+                    .partition(|operand| matches!(operand.kind(), ty::ConstKind::Value(..)));
+                match literals.iter().try_fold(None, |folded, &literal| match folded {
+                    None => Some(Some(literal)),
+                    Some(folded) => fold_binop(tcx, op, lhs_ty, folded, literal).map(Some),
+                }) {
+                    Some(folded) => operands.extend(folded),
+                    // Folding failed, e.g. because it overflowed, so keep the
+                    // literals as they are.
+                    None => operands.extend(literals),
+                }
+                operands.sort_by_key(|&operand| operand_rank(operand));
+                operands
+                    .into_iter()
+                    .reduce(|lhs, rhs| {
+                        let expr = ty::Expr::new_binop(tcx, op, lhs_ty, lhs_ty, lhs, rhs);
+                        ty::Const::new_expr(tcx, expr)
+                    })
+                    .unwrap()
+            } else if let Some(folded) = fold_binop(tcx, op, lhs_ty, lhs, rhs) {
+                folded
+            } else {
+                let (lhs, rhs) = if lhs_ty == rhs_ty
+                    && is_commutative(op)
+                    && operand_rank(rhs) < operand_rank(lhs)
+                {
+                    (rhs, lhs)
+                } else {
+                    (lhs, rhs)
+                };
+                ty::Const::new_expr(tcx, ty::Expr::new_binop(tcx, op, lhs_ty, rhs_ty, lhs, rhs))
+            }
+        }
+        ty::ExprKind::UnOp(op) => {
+            let (ty, operand) = expr.unop_args();
+            let operand = normalize_const_expr(tcx, operand);
+            fold_unop(tcx, op, ty, operand).unwrap_or_else(|| {
+                ty::Const::new_expr(tcx, ty::Expr::new_unop(tcx, op, ty, operand))
+            })
+        }
+        ty::ExprKind::FunctionCall | ty::ExprKind::Cast(_) => {
+            let args = tcx.mk_args_from_iter(expr.args().iter().map(|arg| match arg.unpack() {
+                GenericArgKind::Const(ct) => normalize_const_expr(tcx, ct).into(),
+                _ => arg,
+            }));
+            ty::Const::new_expr(tcx, ty::Expr::new(expr.kind, args))
+        }
+    }
+}
+
+fn is_commutative(op: BinOp) -> bool {
+    matches!(
+        op,
+        BinOp::Add
+            | BinOp::Mul
+            | BinOp::BitAnd
+            | BinOp::BitOr
+            | BinOp::BitXor
+            | BinOp::Eq
+            | BinOp::Ne
+    )
+}
+
+fn is_associative(op: BinOp, ty: Ty<'_>) -> bool {
+    match op {
+        BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor => true,
+        // The sum of unsigned integers overflows if and only if one of its
+        // partial sums does, no matter how they are grouped.
+        BinOp::Add => matches!(ty.kind(), ty::Uint(_)),
+        _ => false,
+    }
+}
+
+/// Collects the operands of the chain of `op`s which `ct` is the root of.
+fn flatten<'tcx>(
+    op: BinOp,
+    ty: Ty<'tcx>,
+    ct: ty::Const<'tcx>,
+    operands: &mut Vec<ty::Const<'tcx>>,
+) {
+    if let ty::ConstKind::Expr(expr) = ct.kind() {
+        if expr.kind == ty::ExprKind::Binop(op) {
+            let (lhs_ty, rhs_ty, lhs, rhs) = expr.binop_args();
+            if lhs_ty == ty && rhs_ty == ty {
+                flatten(op, ty, lhs, operands);
+                flatten(op, ty, rhs, operands);
+                return;
+            }
+        }
+    }
+    operands.push(ct);
+}
+
+/// The key by which the operands of commutative operations are ordered.
+/// Operands of the same rank keep their relative order.
+fn operand_rank(ct: ty::Const<'_>) -> (u8, u128) {
+    match ct.kind() {
+        ty::ConstKind::Param(param) => (0, param.index.into()),
+        ty::ConstKind::Expr(_) => (1, 0),
+        ty::ConstKind::Value(_, valtree) => {
+            let bits = valtree.try_to_scalar_int().and_then(|int| int.try_to_bits(int.size()).ok());
+            (3, bits.unwrap_or(0))
+        }
+        _ => (2, 0),
+    }
+}
+
+/// Evaluates `op` on the literals `lhs` and `rhs` of the integer type `ty`,
+/// returning `None` if either isn't a literal or if the operation overflows.
+fn fold_binop<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    op: BinOp,
+    ty: Ty<'tcx>,
+    lhs: ty::Const<'tcx>,
+    rhs: ty::Const<'tcx>,
+) -> Option<ty::Const<'tcx>> {
+    if !matches!(ty.kind(), ty::Int(_) | ty::Uint(_)) {
+        return None;
+    }
+    let size = ty.primitive_size(tcx);
+    let lhs = lhs.try_to_valtree()?.try_to_scalar_int()?.try_to_bits(size).ok()?;
+    let rhs = rhs.try_to_valtree()?.try_to_scalar_int()?.try_to_bits(size).ok()?;
+
+    let bits = if ty.is_signed() {
+        let (lhs, rhs) = (size.sign_extend(lhs) as i128, size.sign_extend(rhs) as i128);
+        let value = match op {
+            BinOp::Add => lhs.checked_add(rhs)?,
+            BinOp::Sub => lhs.checked_sub(rhs)?,
+            BinOp::Mul => lhs.checked_mul(rhs)?,
+            BinOp::BitAnd => lhs & rhs,
+            BinOp::BitOr => lhs | rhs,
+            BinOp::BitXor => lhs ^ rhs,
+            _ => return None,
+        };
+        if value < size.signed_int_min() || value > size.signed_int_max() {
+            return None;
+        }
+        size.truncate(value as u128)
+    } else {
+        let value = match op {
+            BinOp::Add => lhs.checked_add(rhs)?,
+            BinOp::Sub => lhs.checked_sub(rhs)?,
+            BinOp::Mul => lhs.checked_mul(rhs)?,
+            BinOp::BitAnd => lhs & rhs,
+            BinOp::BitOr => lhs | rhs,
+            BinOp::BitXor => lhs ^ rhs,
+            _ => return None,
+        };
+        if value > size.unsigned_int_max() {
+            return None;
+        }
+        value
+    };
+    Some(ty::Const::from_bits(tcx, bits, ty::ParamEnv::empty().and(ty)))
+}
+
+/// Evaluates `op` on the literal `operand` of the integer type `ty`, see
+/// [`fold_binop`].
+fn fold_unop<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    op: UnOp,
+    ty: Ty<'tcx>,
+    operand: ty::Const<'tcx>,
+) -> Option<ty::Const<'tcx>> {
+    if !matches!(ty.kind(), ty::Int(_) | ty::Uint(_)) {
+        return None;
+    }
+    let size = ty.primitive_size(tcx);
+    let operand = operand.try_to_valtree()?.try_to_scalar_int()?.try_to_bits(size).ok()?;
+
+    let bits = match op {
+        UnOp::Not => size.truncate(!operand),
+        UnOp::Neg if ty.is_signed() => {
+            let value = (size.sign_extend(operand) as i128).checked_neg()?;
+            if value > size.signed_int_max() {
+                return None;
+            }
+            size.truncate(value as u128)
+        }
+        _ => return None,
+    };
+    Some(ty::Const::from_bits(tcx, bits, ty::ParamEnv::empty().and(ty)))
+}
@@ -0,0 +1,118 @@
+//! A thread-local stack of the values currently being related.
+//!
+//! Entry points into the type relation machinery (e.g. `infcx.at(..).eq(..)`)
+//! push a frame describing the top-level values and the cause of the relation.
+//! When the compiler ICEs while relating, e.g. because of a `bug!` in
+//! `structurally_relate_tys`, the panic hook prints this stack, similar to
+//! how it prints the query stack.
+
+use rustc_errors::DiagCtxt;
+use std::cell::Cell;
+use std::fmt;
+use std::io::Write;
+use std::ptr;
+
+/// A single entry of the relate context stack. Frames live on the stack of
+/// the function which entered them, see [`with_relate_context`].
+struct RelateContextFrame<'a> {
+    /// The kind of relation, e.g. `"eq"` or `"sub"`.
+    tag: &'static str,
+    /// The top-level values being related.
+    values: &'a dyn fmt::Debug,
+    /// The obligation cause of the relation, if any.
+    cause: Option<&'a dyn fmt::Debug>,
+    /// The enclosing frame, or null if this is the outermost one.
+    parent: *const (),
+}
+
+thread_local! {
+    /// A thread local variable that stores a pointer to the innermost `RelateContextFrame`.
+    static RELATE_CONTEXT: Cell<*const ()> = const { Cell::new(ptr::null()) };
+}
+
+/// Pushes a frame describing `values` onto the relate context stack for the
+/// duration of `f`. The frame is printed if the compiler panics inside of `f`.
+#[inline]
+pub fn with_relate_context<R>(
+    tag: &'static str,
+    values: &dyn fmt::Debug,
+    cause: Option<&dyn fmt::Debug>,
+    f: impl FnOnce() -> R,
+) -> R {
+    RELATE_CONTEXT.with(|tlv| {
+        let frame = RelateContextFrame { tag, values, cause, parent: tlv.get() };
+        let old = tlv.replace(&frame as *const _ as *const ());
+        let _reset = rustc_data_structures::defer(move || tlv.set(old));
+        f()
+    })
+}
+
+/// Prints the relate context stack of the current thread, innermost frame first.
+///
+/// This is called from the panic hook, so it may only rely on global state
+/// with care. Returns the number of frames printed to `dcx`.
+pub fn print_relate_context(
+    dcx: &DiagCtxt,
+    num_frames: Option<usize>,
+    mut file: Option<std::fs::File>,
+) -> usize {
+    let mut count_printed = 0;
+    let mut count_total = 0;
+
+    if let Some(ref mut file) = file {
+        let _ = writeln!(file, "\n\nrelate context during panic:");
+    }
+    let mut current = RELATE_CONTEXT.get();
+    while !current.is_null() {
+        // SAFETY: frames are only ever set by `with_relate_context`, which resets
+        // the pointer before the frame goes out of scope, even when unwinding.
+        let frame = unsafe { &*(current as *const RelateContextFrame<'_>) };
+        let description = match frame.cause {
+            Some(cause) => format!("relating `{:?}` because of {:?}", frame.values, cause),
+            None => format!("relating `{:?}`", frame.values),
+        };
+
+        if Some(count_printed) < num_frames || num_frames.is_none() {
+            // FIXME: needs translation
+            #[allow(rustc::diagnostic_outside_of_impl)]
+            #[allow(rustc::untranslatable_diagnostic)]
+            dcx.struct_failure_note(format!("#{} [{}] {}", count_printed, frame.tag, description))
+                .emit();
+            count_printed += 1;
+        }
+
+        if let Some(ref mut file) = file {
+            let _ = writeln!(file, "#{} [{}] {}", count_total, frame.tag, description);
+        }
+
+        current = frame.parent;
+        count_total += 1;
+    }
+
+    if let Some(ref mut file) = file {
+        let _ = writeln!(file, "end of relate context");
+    }
+    count_printed
+}
+
+/// Returns whether any values are currently being related on this thread.
+pub fn has_relate_context() -> bool {
+    !RELATE_CONTEXT.get().is_null()
+}
+
+/// Returns the kind, values and cause of the outermost frame of the relate
+/// context stack of this thread, formatted with `Debug`.
+pub(super) fn outermost_relate_context() -> Option<(&'static str, String, Option<String>)> {
+    let mut current = RELATE_CONTEXT.get();
+    let mut outermost = None;
+    while !current.is_null() {
+        // SAFETY: see `print_relate_context`.
+        let frame = unsafe { &*(current as *const RelateContextFrame<'_>) };
+        outermost = Some(frame);
+        current = frame.parent;
+    }
+    outermost.map(|frame| {
+        let cause = frame.cause.map(|cause| format!("{cause:?}"));
+        (frame.tag, format!("{:?}", frame.values), cause)
+    })
+}
@@ -0,0 +1,133 @@
+//! Tracking of how deeply nested the types currently being related are.
+//!
+//! Pathological or machine-generated types, or constants, can be nested
+//! arbitrarily deep. Rather than overflowing the stack, structural relating
+//! bails out with [`TypeError::RelateDepthLimitReached`] once the depth exceeds
+//! [`TyCtxt::relate_depth_limit`], which users can raise via
+//! `#![recursion_limit]` or `-Zrelate-depth-limit`. The stack is grown on
+//! demand, so raising the limit doesn't turn the error into a crash.
+//!
+//! With `-Zprint-relate-extremes`, the deepest and the widest relation of the
+//! compilation are recorded as well, so that the types responsible for
+//! pathological compile times can be found without a profiler.
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::context::outermost_relate_context;
+use crate::ty::relate::stats::record_relate_depth;
+use crate::ty::relate::RelateResult;
+use crate::ty::{self, Ty, TyCtxt};
+use rustc_session::code_stats::{RelateExtremeInfo, RelateExtremeKind};
+use rustc_session::Limit;
+use std::cell::Cell;
+use std::fmt;
+
+thread_local! {
+    /// The number of nested `structurally_relate_*` calls on this thread.
+    static RELATE_DEPTH: Cell<usize> = const { Cell::new(0) };
+
+    /// The relate depth limit, read once by the outermost `structurally_relate_*`
+    /// call so that nested calls don't run a query each.
+    static RELATE_DEPTH_LIMIT: Cell<Limit> = const { Cell::new(Limit(0)) };
+}
+
+/// Runs `f`, the computation of a query, starting from a relate depth of zero.
+///
+/// Queries may be forced while relating, e.g. to evaluate constants. Their
+/// results, including whether they reach the relate depth limit, mustn't
+/// depend on how deeply nested the values related by their caller were.
+#[inline]
+pub fn with_fresh_relate_depth<R>(f: impl FnOnce() -> R) -> R {
+    let depth = RELATE_DEPTH.replace(0);
+    let limit = RELATE_DEPTH_LIMIT.get();
+    let _reset = rustc_data_structures::defer(move || {
+        RELATE_DEPTH.set(depth);
+        RELATE_DEPTH_LIMIT.set(limit);
+    });
+    f()
+}
+
+/// Increments the relate depth for as long as it is alive.
+pub(super) struct RelateDepthGuard(());
+
+impl RelateDepthGuard {
+    /// Enters one more level of structural relating `values`, failing if
+    /// doing so would exceed the relate depth limit of the current crate.
+    #[inline]
+    pub(super) fn enter<'tcx>(
+        tcx: TyCtxt<'tcx>,
+        values: &dyn fmt::Debug,
+    ) -> RelateResult<'tcx, RelateDepthGuard> {
+        let depth = RELATE_DEPTH.get() + 1;
+        let limit = if depth == 1 {
+            let limit = tcx.relate_depth_limit();
+            RELATE_DEPTH_LIMIT.set(limit);
+            limit
+        } else {
+            RELATE_DEPTH_LIMIT.get()
+        };
+        if !limit.value_within_limit(depth) {
+            return Err(TypeError::RelateDepthLimitReached(limit.0));
+        }
+
+        RELATE_DEPTH.set(depth);
+        record_relate_depth(tcx, depth);
+        if tcx.sess.opts.unstable_opts.print_relate_extremes {
+            record_relate_extreme(tcx, RelateExtremeKind::Depth, depth, values);
+        }
+        Ok(RelateDepthGuard(()))
+    }
+}
+
+impl Drop for RelateDepthGuard {
+    #[inline]
+    fn drop(&mut self) {
+        RELATE_DEPTH.set(RELATE_DEPTH.get() - 1);
+    }
+}
+
+/// Records the number of components of `a` which are about to be related to
+/// those of `b`, if `-Zprint-relate-extremes` is enabled.
+#[inline]
+pub(super) fn record_relate_width<'tcx>(tcx: TyCtxt<'tcx>, a: Ty<'tcx>, b: Ty<'tcx>) {
+    if !tcx.sess.opts.unstable_opts.print_relate_extremes {
+        return;
+    }
+
+    let width = match *a.kind() {
+        ty::Tuple(tys) => tys.len(),
+        ty::Adt(_, args)
+        | ty::FnDef(_, args)
+        | ty::Closure(_, args)
+        | ty::CoroutineClosure(_, args)
+        | ty::Coroutine(_, args)
+        | ty::CoroutineWitness(_, args) => args.len(),
+        ty::FnPtr(sig) => sig.skip_binder().inputs_and_output.len(),
+        ty::Dynamic(preds, ..) => preds.len(),
+        _ => return,
+    };
+    record_relate_extreme(tcx, RelateExtremeKind::Width, width, &(a, b));
+}
+
+/// Records the relation currently in progress as the deepest or widest one,
+/// if it exceeds the previous maximum. The outermost values being related are
+/// reported if known, and `values` otherwise.
+#[cold]
+fn record_relate_extreme(
+    tcx: TyCtxt<'_>,
+    kind: RelateExtremeKind,
+    size: usize,
+    values: &dyn fmt::Debug,
+) {
+    let code_stats = &tcx.sess.code_stats;
+    if !code_stats.is_new_relate_extreme(kind, size) {
+        return;
+    }
+
+    let info = match outermost_relate_context() {
+        Some((tag, values, cause)) => RelateExtremeInfo { size, tag, values, cause },
+        None => {
+            RelateExtremeInfo { size, tag: "relate", values: format!("{values:?}"), cause: None }
+        }
+    };
+    code_stats.record_relate_extreme(kind, info);
+}
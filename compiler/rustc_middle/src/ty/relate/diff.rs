@@ -0,0 +1,238 @@
+//! Computing where exactly two types differ.
+//!
+//! A failed relation usually only reports the outermost types that didn't
+//! match, e.g. `TypeError::Sorts` for `Vec<(u32, u8)>` and `Vec<(u32, i8)>`.
+//! Diagnostics which want to point out the part of a large type that differs
+//! can use [`diff_tys`] to get a [`TypeDiff`] tree leading to each mismatch.
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::{self, Relate, RelateResult, TypeRelation};
+use crate::ty::{self, GenericArg, Ty, TyCtxt};
+
+/// The difference between two types, see [`diff_tys`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TypeDiff<'tcx> {
+    /// The two values don't match as a whole, e.g. `u32` and `String`.
+    Leaf { expected: GenericArg<'tcx>, found: GenericArg<'tcx>, err: TypeError<'tcx> },
+    /// The two types have the same structure, e.g. they are both instances of
+    /// the same ADT, but some of their components differ. Each differing
+    /// component is identified by its index, see [`TypeDiffLeaf::path`].
+    Node { expected: Ty<'tcx>, found: Ty<'tcx>, children: Vec<(usize, TypeDiff<'tcx>)> },
+}
+
+/// A single mismatch of a [`TypeDiff`], see [`TypeDiff::leaves`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeDiffLeaf<'tcx> {
+    /// The indices of the components leading from the outermost types to the
+    /// mismatch.
+    ///
+    /// Components are numbered in the order in which they are related, which
+    /// for most types is the order in which they appear in the source: the
+    /// generic args of an ADT, the elements of a tuple, the inputs and then
+    /// the output of a function pointer, or the region and then the pointee
+    /// of a reference.
+    pub path: Vec<usize>,
+    pub expected: GenericArg<'tcx>,
+    pub found: GenericArg<'tcx>,
+    pub err: TypeError<'tcx>,
+}
+
+impl<'tcx> TypeDiff<'tcx> {
+    /// Returns all mismatches of this diff, in the order in which they occur.
+    pub fn leaves(&self) -> Vec<TypeDiffLeaf<'tcx>> {
+        fn collect<'tcx>(
+            diff: &TypeDiff<'tcx>,
+            path: &mut Vec<usize>,
+            leaves: &mut Vec<TypeDiffLeaf<'tcx>>,
+        ) {
+            match *diff {
+                TypeDiff::Leaf { expected, found, err } => {
+                    leaves.push(TypeDiffLeaf { path: path.clone(), expected, found, err })
+                }
+                TypeDiff::Node { ref children, .. } => {
+                    for (index, child) in children {
+                        path.push(*index);
+                        collect(child, path, leaves);
+                        path.pop();
+                    }
+                }
+            }
+        }
+
+        let mut leaves = vec![];
+        collect(self, &mut vec![], &mut leaves);
+        leaves
+    }
+}
+
+/// Returns where `expected` and `found` differ, or `None` if they are equal.
+///
+/// Regions and inference variables are ignored, as diagnostics usually don't
+/// want to point them out.
+pub fn diff_tys<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    expected: Ty<'tcx>,
+    found: Ty<'tcx>,
+) -> Option<TypeDiff<'tcx>> {
+    if expected == found {
+        return None;
+    }
+
+    let mut relation = DiffRelation::new(tcx);
+    let _ = relation.relate(expected, found);
+    relation.into_diff()
+}
+
+/// A type currently being diffed by [`DiffRelation`].
+struct DiffFrame<'tcx> {
+    /// The index of the next component of this type to be related.
+    next_component: usize,
+    children: Vec<(usize, TypeDiff<'tcx>)>,
+}
+
+/// The relation behind [`diff_tys`].
+///
+/// It relates all components of a type even after one of them failed to
+/// relate, see [`TypeRelation::collect_errors`], and records a [`TypeDiff`]
+/// for every mismatch.
+pub struct DiffRelation<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    stack: Vec<DiffFrame<'tcx>>,
+    root: Option<TypeDiff<'tcx>>,
+    /// Only needed to opt into `collect_errors`, the mismatches themselves
+    /// are recorded in `root`.
+    errors: Vec<TypeError<'tcx>>,
+}
+
+impl<'tcx> DiffRelation<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> DiffRelation<'tcx> {
+        DiffRelation { tcx, stack: vec![], root: None, errors: vec![] }
+    }
+
+    /// Returns the diff of the values related so far.
+    pub fn into_diff(self) -> Option<TypeDiff<'tcx>> {
+        self.root
+    }
+
+    /// Returns the index of the component about to be related in the type
+    /// currently being diffed.
+    fn next_component(&mut self) -> usize {
+        match self.stack.last_mut() {
+            Some(frame) => {
+                frame.next_component += 1;
+                frame.next_component - 1
+            }
+            None => 0,
+        }
+    }
+
+    fn record(&mut self, index: usize, diff: TypeDiff<'tcx>) {
+        match self.stack.last_mut() {
+            Some(frame) => frame.children.push((index, diff)),
+            None => self.root = Some(diff),
+        }
+    }
+
+    fn leaf<T: Into<GenericArg<'tcx>>>(
+        &mut self,
+        index: usize,
+        a: T,
+        b: T,
+        err: TypeError<'tcx>,
+    ) -> TypeError<'tcx> {
+        self.record(index, TypeDiff::Leaf { expected: a.into(), found: b.into(), err });
+        err
+    }
+}
+
+impl<'tcx> TypeRelation<'tcx> for DiffRelation<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "DiffRelation"
+    }
+
+    fn collect_errors(&mut self) -> Option<&mut Vec<TypeError<'tcx>>> {
+        Some(&mut self.errors)
+    }
+
+    fn can_shortcircuit_on_equality(&self) -> bool {
+        // Skipping equal components would throw off their indices.
+        false
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        _: ty::Variance,
+        _: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        self.relate(a, b)
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        let index = self.next_component();
+        if a == b {
+            return Ok(a);
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::Infer(_), _) | (_, ty::Infer(_)) => return Ok(a),
+            (ty::Bound(..), _) | (_, ty::Bound(..)) => {
+                return Err(self.leaf(index, a, b, TypeError::Sorts(relate::expected_found(a, b))));
+            }
+            _ => {}
+        }
+
+        self.stack.push(DiffFrame { next_component: 0, children: vec![] });
+        let result = relate::structurally_relate_tys(self, a, b);
+        let frame = self.stack.pop().unwrap();
+        match result {
+            Err(err) if frame.children.is_empty() => Err(self.leaf(index, a, b, err)),
+            Err(err) => {
+                let children = frame.children;
+                self.record(index, TypeDiff::Node { expected: a, found: b, children });
+                Err(err)
+            }
+            Ok(ty) => Ok(ty),
+        }
+    }
+
+    fn regions(
+        &mut self,
+        a: ty::Region<'tcx>,
+        _: ty::Region<'tcx>,
+    ) -> RelateResult<'tcx, ty::Region<'tcx>> {
+        self.next_component();
+        Ok(a)
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        let index = self.next_component();
+        match (a.kind(), b.kind()) {
+            _ if a == b => Ok(a),
+            (ty::ConstKind::Infer(_), _) | (_, ty::ConstKind::Infer(_)) => Ok(a),
+            _ => {
+                Err(self.leaf(index, a, b, TypeError::ConstMismatch(relate::expected_found(a, b))))
+            }
+        }
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        Ok(a.rebind(self.relate(a.skip_binder(), b.skip_binder())?))
+    }
+}
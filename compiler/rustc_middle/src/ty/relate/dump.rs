@@ -0,0 +1,243 @@
+//! A JSON-lines trace of the top-level type relations of a compilation.
+//!
+//! Finding out why two types failed to unify usually means grepping huge
+//! `RUSTC_LOG` outputs. With `-Zdump-type-relations=<path>`, the entry points
+//! of the type relation machinery, e.g. `infcx.at(..).eq(..)`, wrap their
+//! relation in [`DumpRelation`], which writes one JSON object per relation to
+//! `<path>`, replacing the file if it already exists, with the following fields:
+//!
+//! - `depth`: the number of dumped relations the relation is nested in,
+//! - `tag`: the kind of relation, e.g. `"eq"`,
+//! - `variance`: the variance with which the values are related, or `null`
+//!   when computing their least upper or greatest lower bound,
+//! - `a` and `b`: the related values, printed with full paths,
+//! - `a_hash` and `b_hash`: stable hashes of the printed values, which allow
+//!   finding the same values across lines and compilations,
+//! - `result`: `"ok"`, or the error the relation failed with.
+//!
+//! Each line is written once its relation is done, so nested relations come
+//! before the relation they are nested in.
+
+use crate::ty::error::TypeError;
+use crate::ty::print::with_no_trimmed_paths;
+use crate::ty::relate::{
+    Relate, RelateCache, RelateCapabilities, RelateResult, StructurallyRelateAliases,
+    TypeRelation,
+};
+use crate::ty::{self, GenericArgsRef, Ty, TyCtxt};
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_target::spec::abi;
+use std::cell::Cell;
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{BufWriter, Write as _};
+
+thread_local! {
+    /// The number of dumped relations currently in progress on this thread.
+    static DUMP_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Wraps a relation, dumping the values it relates at the top level if
+/// `-Zdump-type-relations` is enabled, see the module docs.
+pub struct DumpRelation<R> {
+    relation: R,
+    variance: Option<ty::Variance>,
+}
+
+impl<R> DumpRelation<R> {
+    /// Wraps `relation`, which relates values with `variance`, or computes
+    /// their least upper or greatest lower bound if `variance` is `None`.
+    pub fn new(relation: R, variance: Option<ty::Variance>) -> DumpRelation<R> {
+        DumpRelation { relation, variance }
+    }
+}
+
+impl<'tcx, R: TypeRelation<'tcx>> TypeRelation<'tcx> for DumpRelation<R> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.relation.tcx()
+    }
+
+    fn tag(&self) -> &'static str {
+        self.relation.tag()
+    }
+
+    fn relate<T: Relate<'tcx>>(&mut self, a: T, b: T) -> RelateResult<'tcx, T> {
+        let tcx = self.tcx();
+        if tcx.sess.opts.unstable_opts.dump_type_relations.is_none() {
+            return self.relation.relate(a, b);
+        }
+
+        let depth = DUMP_DEPTH.replace(DUMP_DEPTH.get() + 1);
+        let _reset = rustc_data_structures::defer(move || DUMP_DEPTH.set(depth));
+        let result = self.relation.relate(a, b);
+        dump_relation(tcx, depth, self.tag(), self.variance, &a, &b, result.as_ref().err());
+        result
+    }
+
+    fn relate_item_args(
+        &mut self,
+        item_def_id: DefId,
+        a_arg: GenericArgsRef<'tcx>,
+        b_arg: GenericArgsRef<'tcx>,
+    ) -> RelateResult<'tcx, GenericArgsRef<'tcx>> {
+        self.relation.relate_item_args(item_def_id, a_arg, b_arg)
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        variance: ty::Variance,
+        info: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        self.relation.relate_with_variance(variance, info, a, b)
+    }
+
+    fn relate_fresh_tys_by_index(&self) -> bool {
+        self.relation.relate_fresh_tys_by_index()
+    }
+
+    fn normalize_alias_hint(&mut self, alias: ty::AliasTy<'tcx>) -> Option<Ty<'tcx>> {
+        self.relation.normalize_alias_hint(alias)
+    }
+
+    fn structurally_relate_aliases(&self) -> StructurallyRelateAliases {
+        self.relation.structurally_relate_aliases()
+    }
+
+    fn normalize_const_exprs(&self) -> bool {
+        self.relation.normalize_const_exprs()
+    }
+
+    fn collect_errors(&mut self) -> Option<&mut Vec<TypeError<'tcx>>> {
+        self.relation.collect_errors()
+    }
+
+    fn capabilities(&self) -> RelateCapabilities {
+        self.relation.capabilities()
+    }
+
+    fn cache(&mut self) -> Option<(ty::Variance, &mut RelateCache<'tcx>)> {
+        self.relation.cache()
+    }
+
+    fn can_shortcircuit_on_equality(&self) -> bool {
+        self.relation.can_shortcircuit_on_equality()
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        self.relation.tys(a, b)
+    }
+
+    fn regions(
+        &mut self,
+        a: ty::Region<'tcx>,
+        b: ty::Region<'tcx>,
+    ) -> RelateResult<'tcx, ty::Region<'tcx>> {
+        self.relation.regions(a, b)
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        self.relation.consts(a, b)
+    }
+
+    fn safeties(&mut self, a: hir::Safety, b: hir::Safety) -> RelateResult<'tcx, hir::Safety> {
+        self.relation.safeties(a, b)
+    }
+
+    fn abis(&mut self, a: abi::Abi, b: abi::Abi) -> RelateResult<'tcx, abi::Abi> {
+        self.relation.abis(a, b)
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        self.relation.binders(a, b)
+    }
+}
+
+/// Writes a line describing a relation to the dump file, see the module docs.
+#[cold]
+fn dump_relation<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    depth: usize,
+    tag: &'static str,
+    variance: Option<ty::Variance>,
+    a: &dyn fmt::Debug,
+    b: &dyn fmt::Debug,
+    error: Option<&TypeError<'tcx>>,
+) {
+    let (a, b) = with_no_trimmed_paths!((format!("{a:?}"), format!("{b:?}")));
+    let hash = |value: &str| {
+        let mut hasher = StableHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish::<Fingerprint>().to_hex()
+    };
+    let result = match error {
+        Some(error) => with_no_trimmed_paths!(error.to_string(tcx).into_owned()),
+        None => "ok".to_owned(),
+    };
+
+    let mut line = String::new();
+    let _ = write!(line, "{{\"depth\":{depth},\"tag\":");
+    write_json_str(&mut line, tag);
+    line.push_str(",\"variance\":");
+    match variance {
+        Some(variance) => write_json_str(&mut line, &format!("{variance:?}")),
+        None => line.push_str("null"),
+    }
+    line.push_str(",\"a\":");
+    write_json_str(&mut line, &a);
+    line.push_str(",\"b\":");
+    write_json_str(&mut line, &b);
+    let _ = write!(line, ",\"a_hash\":\"{}\",\"b_hash\":\"{}\"", hash(&a), hash(&b));
+    line.push_str(",\"result\":");
+    write_json_str(&mut line, &result);
+    line.push_str("}\n");
+
+    let mut file = tcx.type_relations_dump.lock();
+    if file.is_none() {
+        let path = tcx.sess.opts.unstable_opts.dump_type_relations.as_ref().unwrap();
+        match File::create(path) {
+            Ok(created) => *file = Some(BufWriter::new(created)),
+            Err(err) => {
+                let path = path.display();
+                tcx.dcx().fatal(format!("failed to create type relation dump `{path}`: {err}"))
+            }
+        }
+    }
+    if let Err(err) = file.as_mut().unwrap().write_all(line.as_bytes()) {
+        tcx.dcx().fatal(format!("failed to write type relation dump: {err}"));
+    }
+}
+
+/// Writes `s` to `out` as a JSON string literal.
+fn write_json_str(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
@@ -0,0 +1,135 @@
+//! Equality of types after monomorphization.
+//!
+//! Codegen and the monomorphization collector compare types which have had
+//! their regions erased. Comparing them with `==` is usually enough, but
+//! treats types as different if they only differ in whether a constant has
+//! been evaluated yet, or in the regions which remain after erasure, such as
+//! bound regions.
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::{self, Relate, RelateCapabilities, RelateResult, TypeRelation};
+use crate::ty::{self, Ty, TyCtxt};
+
+/// Returns whether `a` and `b` are the same type, ignoring all regions and
+/// evaluating constants in `param_env` where necessary.
+///
+/// Both types are expected to be fully normalized. Aliases are only equal to
+/// themselves, up to their regions.
+pub fn erased_types_equal<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    a: Ty<'tcx>,
+    b: Ty<'tcx>,
+) -> bool {
+    if a == b {
+        return true;
+    }
+
+    let equal = ErasedEq { tcx, param_env }.relate(a, b).is_ok();
+    if cfg!(debug_assertions) && !equal && tcx.erase_regions(a) == tcx.erase_regions(b) {
+        bug!("`{a:?}` and `{b:?}` are equal after erasing regions, but don't relate as equal");
+    }
+    equal
+}
+
+/// The relation behind [`erased_types_equal`].
+struct ErasedEq<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+}
+
+impl<'tcx> TypeRelation<'tcx> for ErasedEq<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "ErasedEq"
+    }
+
+    fn capabilities(&self) -> RelateCapabilities {
+        // Regions are ignored, see the module docs.
+        RelateCapabilities::WANTS_DIAGNOSTICS | RelateCapabilities::CONST_EVAL
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        _: ty::Variance,
+        _: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        self.relate(a, b)
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        if a == b {
+            return Ok(a);
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::Infer(_), _) | (_, ty::Infer(_)) => {
+                bug!("unexpected inference variable when comparing `{a:?}` and `{b:?}`")
+            }
+            (ty::Alias(..), _) | (_, ty::Alias(..)) => {
+                // Regions bound outside of the aliases escape them, so they
+                // survive `erase_regions` and have to be erased separately.
+                let tcx = self.tcx;
+                let erase = |ty: Ty<'tcx>| {
+                    tcx.erase_regions(tcx.fold_regions(ty, |_, _| tcx.lifetimes.re_erased))
+                };
+                if erase(a) == erase(b) {
+                    Ok(a)
+                } else {
+                    Err(TypeError::Sorts(relate::expected_found(a, b)))
+                }
+            }
+            // The names of bound types are ignored like those of bound regions.
+            (ty::Bound(a_debruijn, a_bound), ty::Bound(b_debruijn, b_bound))
+                if a_debruijn == b_debruijn && a_bound.var == b_bound.var =>
+            {
+                Ok(a)
+            }
+            (ty::Bound(..), _) | (_, ty::Bound(..)) => {
+                Err(TypeError::Sorts(relate::expected_found(a, b)))
+            }
+            _ => relate::structurally_relate_tys(self, a, b),
+        }
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        if a == b {
+            return Ok(a);
+        }
+
+        let a = a.normalize(self.tcx, self.param_env);
+        let b = b.normalize(self.tcx, self.param_env);
+        if a == b {
+            return Ok(a);
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::ConstKind::Infer(_), _) | (_, ty::ConstKind::Infer(_)) => {
+                bug!("unexpected inference variable when comparing `{a:?}` and `{b:?}`")
+            }
+            _ => relate::structurally_relate_consts(self, a, b),
+        }
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        // Bound regions are ignored like all other regions. Bound types and
+        // consts only relate if they are the same, see `tys`.
+        Ok(a.rebind(self.relate(a.skip_binder(), b.skip_binder())?))
+    }
+}
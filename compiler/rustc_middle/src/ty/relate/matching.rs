@@ -0,0 +1,149 @@
+//! Matching a value against a pattern with holes.
+//!
+//! Lints and diagnostics often want to know whether a concrete type has the
+//! shape of some generic type, e.g. whether `Vec<Option<u32>>` is a
+//! `Vec<Option<T>>`, and what the parameters of the generic type correspond
+//! to. [`Match`] answers this without an inference context.
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::{self, Bindings, Relate, RelateCapabilities, RelateResult, TypeRelation};
+use crate::ty::{self, InferConst, Ty, TyCtxt, TypeVisitableExt};
+
+/// A relation matching a concrete value against a pattern.
+///
+/// The holes of the pattern are its type, const, and early-bound region
+/// parameters as well as its fresh inference variables, see [`ty::FreshTy`].
+/// Each hole matches any value without escaping bound vars, as long as all of
+/// its occurrences match the same value. All other parts of the pattern have to be structurally equal
+/// to the value, except for regions, which always match.
+///
+/// The pattern is always the first value passed to the relation, and the
+/// relation returns the pattern with its holes replaced by the values they
+/// matched.
+pub struct Match<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    bindings: Bindings<'tcx>,
+}
+
+impl<'tcx> Match<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> Match<'tcx> {
+        Match { tcx, bindings: Bindings::default() }
+    }
+
+    /// Matches `value` against `pattern`, returning the values the holes of
+    /// `pattern` matched.
+    pub fn match_value<T: Relate<'tcx>>(
+        mut self,
+        pattern: T,
+        value: T,
+    ) -> RelateResult<'tcx, Bindings<'tcx>> {
+        self.relate(pattern, value)?;
+        Ok(self.bindings)
+    }
+
+    /// Returns the values the holes of the patterns related so far matched.
+    pub fn into_bindings(self) -> Bindings<'tcx> {
+        self.bindings
+    }
+}
+
+impl<'tcx> TypeRelation<'tcx> for Match<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "Match"
+    }
+
+    fn capabilities(&self) -> RelateCapabilities {
+        RelateCapabilities::CONST_EVAL
+    }
+
+    fn can_shortcircuit_on_equality(&self) -> bool {
+        // Holes have to be bound even if they are matched against themselves.
+        false
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        _: ty::Variance,
+        _: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        self.relate(a, b)
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        match (a.kind(), b.kind()) {
+            (
+                ty::Param(_) | ty::Infer(ty::FreshTy(_) | ty::FreshIntTy(_) | ty::FreshFloatTy(_)),
+                _,
+            ) => {
+                // A hole can't match a value with escaping bound vars, as the
+                // binding would refer to a binder it is not inside of.
+                if b.has_escaping_bound_vars() {
+                    return Err(TypeError::Sorts(relate::expected_found(a, b)));
+                }
+                self.bindings
+                    .bind(a.into(), b.into())
+                    .map_err(|()| TypeError::Sorts(relate::expected_found(a, b)))?;
+                Ok(b)
+            }
+            (ty::Infer(_), _) | (_, ty::Infer(_)) => {
+                Err(TypeError::Sorts(relate::expected_found(a, b)))
+            }
+            (ty::Bound(..), _) | (_, ty::Bound(..)) => {
+                if a == b { Ok(a) } else { Err(TypeError::Sorts(relate::expected_found(a, b))) }
+            }
+            _ => relate::structurally_relate_tys(self, a, b),
+        }
+    }
+
+    fn regions(
+        &mut self,
+        a: ty::Region<'tcx>,
+        b: ty::Region<'tcx>,
+    ) -> RelateResult<'tcx, ty::Region<'tcx>> {
+        // Regions always match, so an early-bound region parameter which is
+        // matched against different regions keeps the first one.
+        if let ty::ReEarlyParam(_) = a.kind() {
+            let _ = self.bindings.bind(a.into(), b.into());
+        }
+        Ok(b)
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        match (a.kind(), b.kind()) {
+            (ty::ConstKind::Param(_) | ty::ConstKind::Infer(InferConst::Fresh(_)), _) => {
+                if b.has_escaping_bound_vars() {
+                    return Err(TypeError::ConstMismatch(relate::expected_found(a, b)));
+                }
+                self.bindings
+                    .bind(a.into(), b.into())
+                    .map_err(|()| TypeError::ConstMismatch(relate::expected_found(a, b)))?;
+                Ok(b)
+            }
+            (ty::ConstKind::Infer(_), _) | (_, ty::ConstKind::Infer(_)) => {
+                Err(TypeError::ConstMismatch(relate::expected_found(a, b)))
+            }
+            _ => relate::structurally_relate_consts(self, a, b),
+        }
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        Ok(a.rebind(self.relate(a.skip_binder(), b.skip_binder())?))
+    }
+}
@@ -0,0 +1,111 @@
+//! Structural equality of fully resolved types, ignoring regions.
+//!
+//! Rustdoc, when deduplicating impls and bounds, and diagnostics, when
+//! deciding whether two types are worth pointing out as different, need to
+//! know whether two types are the same up to their regions and unnormalized
+//! aliases, but have no inference context to equate them in. The
+//! [`structurally_equal_modulo_regions`] query answers this with the
+//! [`EqualModuloRegions`] relation.
+//!
+//! [`structurally_equal_modulo_regions`]: TyCtxt::structurally_equal_modulo_regions
+
+use crate::query::Providers;
+use crate::ty::relate::{self, Relate, RelateCapabilities, RelateResult, TypeRelation};
+use crate::ty::{self, Ty, TyCtxt};
+
+pub(crate) fn provide(providers: &mut Providers) {
+    *providers = Providers { structurally_equal_modulo_regions, ..*providers };
+}
+
+fn structurally_equal_modulo_regions<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    key: ty::ParamEnvAnd<'tcx, (Ty<'tcx>, Ty<'tcx>)>,
+) -> bool {
+    let (param_env, (a, b)) = key.into_parts();
+    let normalize = |ty| {
+        tcx.try_normalize_erasing_regions(param_env, ty).unwrap_or_else(|_| tcx.erase_regions(ty))
+    };
+    let (a, b) = (normalize(a), normalize(b));
+    a == b || EqualModuloRegions { tcx }.relate(a, b).is_ok()
+}
+
+/// The relation behind the [`structurally_equal_modulo_regions`] query,
+/// which relates values with erased regions and normalized aliases.
+///
+/// All regions relate, and error types and consts relate to everything.
+/// Binders relate if they bind the same kinds of variables, see
+/// [`relate::relate_higher_ranked`].
+///
+/// [`structurally_equal_modulo_regions`]: TyCtxt::structurally_equal_modulo_regions
+struct EqualModuloRegions<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> TypeRelation<'tcx> for EqualModuloRegions<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "EqualModuloRegions"
+    }
+
+    fn capabilities(&self) -> RelateCapabilities {
+        // The result of a query mustn't depend on whether we're emitting
+        // diagnostics, and regions are ignored, see the module docs.
+        RelateCapabilities::CONST_EVAL
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        _: ty::Variance,
+        _: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        self.relate(a, b)
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        if a == b {
+            return Ok(a);
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::Infer(_), _) | (_, ty::Infer(_)) => {
+                bug!("unexpected inference variable when comparing `{a:?}` and `{b:?}`")
+            }
+            (ty::Error(_), _) | (_, ty::Error(_)) => Ok(a),
+            _ => relate::structurally_relate_tys(self, a, b),
+        }
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        if a == b {
+            return Ok(a);
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::ConstKind::Infer(_), _) | (_, ty::ConstKind::Infer(_)) => {
+                bug!("unexpected inference variable when comparing `{a:?}` and `{b:?}`")
+            }
+            (ty::ConstKind::Error(_), _) | (_, ty::ConstKind::Error(_)) => Ok(a),
+            _ => relate::structurally_relate_consts(self, a, b),
+        }
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        relate::relate_higher_ranked(self, a, b)
+    }
+}
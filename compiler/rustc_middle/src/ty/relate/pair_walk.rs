@@ -0,0 +1,163 @@
+//! Walking two types in lockstep.
+//!
+//! Diagnostics and suggestions often want to look at the parts of two types
+//! which correspond to each other, e.g. to point at the argument of a function
+//! pointer which has a different type than the one expected. Instead of
+//! zipping the types by hand, [`pair_walk`] uses the structural relation code
+//! to find the corresponding components, together with their variance.
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::{self, Relate, RelateCapabilities, RelateResult, TypeRelation};
+use crate::ty::{self, GenericArg, Ty, TyCtxt};
+
+/// Two corresponding components of the types passed to [`pair_walk`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlignedPair<'tcx> {
+    /// The indices of the components leading from the outermost types to this
+    /// pair, numbered like [`TypeDiffLeaf::path`](super::TypeDiffLeaf::path).
+    pub path: Vec<usize>,
+    /// The variance of this pair relative to the outermost types.
+    pub variance: ty::Variance,
+    pub a: GenericArg<'tcx>,
+    pub b: GenericArg<'tcx>,
+}
+
+/// Returns the pairs of corresponding components of `a` and `b`, starting with
+/// `(a, b)` itself and in the order in which they are related.
+///
+/// The walk descends into a pair as long as the structure of its two sides
+/// matches, and stops at the first pair whose structure differs, which is the
+/// last pair returned. Pairs which are equal, contain inference variables,
+/// or are regions are returned, but not descended into.
+pub fn pair_walk<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    a: Ty<'tcx>,
+    b: Ty<'tcx>,
+) -> impl Iterator<Item = AlignedPair<'tcx>> {
+    let mut walker =
+        PairWalker { tcx, ambient_variance: ty::Covariant, frames: vec![], pairs: vec![] };
+    let _ = walker.relate(a, b);
+    walker.pairs.into_iter()
+}
+
+/// A pair currently being walked by [`PairWalker`].
+struct Frame {
+    path: Vec<usize>,
+    /// The index of the next component of this pair to be walked.
+    next_component: usize,
+}
+
+/// The relation behind [`pair_walk`].
+struct PairWalker<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    ambient_variance: ty::Variance,
+    frames: Vec<Frame>,
+    pairs: Vec<AlignedPair<'tcx>>,
+}
+
+impl<'tcx> PairWalker<'tcx> {
+    /// Records the pair `(a, b)` as the next component of the pair currently
+    /// being walked, returning its path.
+    fn record<T: Into<GenericArg<'tcx>>>(&mut self, a: T, b: T) -> Vec<usize> {
+        let path = match self.frames.last_mut() {
+            Some(frame) => {
+                let mut path = frame.path.clone();
+                path.push(frame.next_component);
+                frame.next_component += 1;
+                path
+            }
+            None => vec![],
+        };
+        let variance = self.ambient_variance;
+        self.pairs.push(AlignedPair { path: path.clone(), variance, a: a.into(), b: b.into() });
+        path
+    }
+
+    fn descend<T>(&mut self, path: Vec<usize>, walk: impl FnOnce(&mut Self) -> T) -> T {
+        self.frames.push(Frame { path, next_component: 0 });
+        let result = walk(self);
+        self.frames.pop();
+        result
+    }
+}
+
+impl<'tcx> TypeRelation<'tcx> for PairWalker<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "PairWalker"
+    }
+
+    fn capabilities(&self) -> RelateCapabilities {
+        RelateCapabilities::CONST_EVAL
+    }
+
+    fn can_shortcircuit_on_equality(&self) -> bool {
+        // Equal components are part of the walk as well.
+        false
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        variance: ty::Variance,
+        _: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        let old_ambient_variance = self.ambient_variance;
+        self.ambient_variance = self.ambient_variance.xform(variance);
+        let r = self.relate(a, b);
+        self.ambient_variance = old_ambient_variance;
+        r
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        let path = self.record(a, b);
+        if a == b {
+            return Ok(a);
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::Infer(_), _) | (_, ty::Infer(_)) => Ok(a),
+            (ty::Bound(..), _) | (_, ty::Bound(..)) => {
+                Err(TypeError::Sorts(relate::expected_found(a, b)))
+            }
+            _ => self.descend(path, |this| relate::structurally_relate_tys(this, a, b)),
+        }
+    }
+
+    fn regions(
+        &mut self,
+        a: ty::Region<'tcx>,
+        b: ty::Region<'tcx>,
+    ) -> RelateResult<'tcx, ty::Region<'tcx>> {
+        self.record(a, b);
+        Ok(a)
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        let path = self.record(a, b);
+        match (a.kind(), b.kind()) {
+            _ if a == b => Ok(a),
+            (ty::ConstKind::Infer(_), _) | (_, ty::ConstKind::Infer(_)) => Ok(a),
+            _ => self.descend(path, |this| relate::structurally_relate_consts(this, a, b)),
+        }
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        Ok(a.rebind(self.relate(a.skip_binder(), b.skip_binder())?))
+    }
+}
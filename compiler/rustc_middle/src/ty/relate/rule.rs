@@ -0,0 +1,109 @@
+//! Stable identifiers for the structural rules applied while relating values.
+//!
+//! Every time the structural relation code descends into a component of a
+//! value, it emits a `relate_rule` trace event naming the rule it applied,
+//! e.g. `AdtArgs` or `FnSigInput(2)`. Tools consuming `RUSTC_LOG` output can
+//! use these events to reconstruct which rules fired, without having to parse
+//! the `Debug` output of the values being related.
+//!
+//! The names printed by the `Display` impl of [`RelateRule`] are stable and
+//! must not be changed. New rules may be added at any time.
+
+use std::fmt;
+
+use tracing::debug;
+
+/// A structural rule of the type relation machinery, see the module docs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RelateRule {
+    /// Relating two equal primitive types, e.g. `bool` and `bool`.
+    Primitive,
+    /// Relating two type or const parameters with the same index.
+    Param,
+    /// Relating two equal placeholders.
+    Placeholder,
+    /// Relating a value with an error.
+    Error,
+    /// Relating the generic args of two instances of the same ADT.
+    AdtArgs,
+    /// Relating two equal foreign types.
+    Foreign,
+    /// Relating the principal traits of two trait objects.
+    DynPrincipal,
+    /// Relating the projection bounds at index `i` of the predicate lists of
+    /// two trait objects.
+    DynProjection(usize),
+    /// Relating the auto traits of two trait objects.
+    DynAutoTrait,
+    /// Relating the region bounds of two trait objects.
+    DynRegion,
+    /// Relating the args of two coroutines with the same id.
+    CoroutineArgs,
+    /// Relating the args of two coroutine witnesses with the same id.
+    CoroutineWitnessArgs,
+    /// Relating the args of two closures with the same id.
+    ClosureArgs,
+    /// Relating the args of two coroutine-closures with the same id.
+    CoroutineClosureArgs,
+    /// Relating the pointees of two raw pointers.
+    RawPtrPointee,
+    /// Relating the regions of two references.
+    RefRegion,
+    /// Relating the pointees of two references.
+    RefPointee,
+    /// Relating the element types of two arrays.
+    ArrayElement,
+    /// Relating the lengths of two arrays.
+    ArrayLen,
+    /// Relating the element types of two slices.
+    SliceElement,
+    /// Relating the `i`th elements of two tuples.
+    TupleElement(usize),
+    /// Relating the generic args of two function items with the same def id.
+    FnDefArgs,
+    /// Relating the signatures of two function pointers.
+    FnPtrSig,
+    /// Relating the safety of two function signatures.
+    FnSigSafety,
+    /// Relating the ABIs of two function signatures.
+    FnSigAbi,
+    /// Relating the `i`th inputs of two function signatures.
+    FnSigInput(usize),
+    /// Relating the outputs of two function signatures.
+    FnSigOutput,
+    /// Relating the args of two aliases of the same item.
+    AliasArgs,
+    /// Relating aliases after normalizing them, see
+    /// [`TypeRelation::normalize_alias_hint`](super::TypeRelation::normalize_alias_hint).
+    NormalizedAlias,
+    /// Relating the base types of two pattern types.
+    PatBase,
+    /// Relating the patterns of two pattern types.
+    Pattern,
+    /// Relating the args of two unevaluated consts of the same item.
+    UnevaluatedConstArgs,
+    /// Relating the operands of two const expressions of the same kind.
+    ConstExprArgs,
+    /// Relating the generic args of two trait refs of the same trait.
+    TraitRefArgs,
+}
+
+impl fmt::Display for RelateRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RelateRule::DynProjection(i) => write!(f, "DynProjection({i})"),
+            RelateRule::TupleElement(i) => write!(f, "TupleElement({i})"),
+            RelateRule::FnSigInput(i) => write!(f, "FnSigInput({i})"),
+            // The remaining rules have no fields, so their `Debug` output is
+            // just their name.
+            rule => fmt::Debug::fmt(&rule, f),
+        }
+    }
+}
+
+/// Emits the trace event recording that `rule` is about to be applied by the
+/// relation `tag`.
+#[inline]
+pub(super) fn trace_rule(tag: &'static str, rule: RelateRule) {
+    debug!(relation = tag, relate_rule = %rule, "applying relate rule");
+}
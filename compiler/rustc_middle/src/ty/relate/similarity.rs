@@ -0,0 +1,170 @@
+//! Scoring how similar two types are.
+//!
+//! Diagnostics which suggest the closest of several candidates, e.g. a method
+//! which exists for `Rc<Foo>` when it was called on a `Foo`, need to rank the
+//! candidates by how close their types are to the one at hand. [`similarity`]
+//! walks both types like a relation and adds up a penalty for every part in
+//! which they differ, instead of stopping at the first mismatch.
+
+use crate::ty::relate::{self, Relate, RelateCapabilities, RelateResult, TypeRelation};
+use crate::ty::{self, Ty, TyCtxt};
+
+/// The penalty for two types of a different kind, e.g. two different ADTs.
+const DIFFERENT_TYPES: u32 = 16;
+/// The penalty for a type which wraps the other type, e.g. `Rc<Foo>` and
+/// `Foo`, or `&Foo` and `Foo`.
+const WRAPPED_TYPE: u32 = 4;
+/// The penalty for two different numeric types, e.g. `u32` and `i64`.
+const DIFFERENT_NUMERIC_TYPES: u32 = 4;
+/// The penalty for two different constants, e.g. array lengths.
+const DIFFERENT_CONSTS: u32 = 4;
+/// The penalty for references or pointers of different mutability.
+const DIFFERENT_MUTABILITY: u32 = 2;
+/// The penalty for two different regions.
+const DIFFERENT_REGIONS: u32 = 1;
+
+/// Returns how different `a` and `b` are, where `0` means that the types are
+/// equal, up to inference variables, and larger scores mean less similar types.
+///
+/// The score is only meant for comparing candidates with each other, its
+/// absolute value carries no meaning.
+pub fn similarity<'tcx>(tcx: TyCtxt<'tcx>, a: Ty<'tcx>, b: Ty<'tcx>) -> u32 {
+    let mut scorer = SimilarityScorer::new(tcx);
+    let _ = scorer.relate(a, b);
+    scorer.score()
+}
+
+/// A relation which never fails, but instead accumulates a penalty for all
+/// parts in which the related values differ, see [`similarity`].
+pub struct SimilarityScorer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    score: u32,
+}
+
+impl<'tcx> SimilarityScorer<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> SimilarityScorer<'tcx> {
+        SimilarityScorer { tcx, score: 0 }
+    }
+
+    /// Returns the total penalty of the values related so far.
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    fn penalize(&mut self, penalty: u32) {
+        self.score = self.score.saturating_add(penalty);
+    }
+}
+
+/// Whether `outer` directly wraps `inner`, i.e. `inner` is the pointee of the
+/// reference or pointer `outer` or a generic argument of the ADT `outer`.
+fn wraps<'tcx>(outer: Ty<'tcx>, inner: Ty<'tcx>) -> bool {
+    match *outer.kind() {
+        ty::Ref(_, ty, _) | ty::RawPtr(ty, _) => ty == inner,
+        ty::Adt(_, args) => args.types().any(|ty| ty == inner),
+        _ => false,
+    }
+}
+
+impl<'tcx> TypeRelation<'tcx> for SimilarityScorer<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "SimilarityScorer"
+    }
+
+    fn capabilities(&self) -> RelateCapabilities {
+        RelateCapabilities::empty()
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        _: ty::Variance,
+        _: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        self.relate(a, b)
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        if a == b {
+            return Ok(a);
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::Infer(_), _) | (_, ty::Infer(_)) | (ty::Error(_), _) | (_, ty::Error(_)) => {
+                return Ok(a);
+            }
+            (ty::Bound(..), _) | (_, ty::Bound(..)) => {
+                self.penalize(DIFFERENT_TYPES);
+                return Ok(a);
+            }
+            (&ty::Ref(_, a_ty, a_mutbl), &ty::Ref(_, b_ty, b_mutbl))
+            | (&ty::RawPtr(a_ty, a_mutbl), &ty::RawPtr(b_ty, b_mutbl))
+                if a_mutbl != b_mutbl =>
+            {
+                // Structurally relating them would stop at the mutability, so
+                // score the pointees separately.
+                self.penalize(DIFFERENT_MUTABILITY);
+                self.relate(a_ty, b_ty)?;
+                return Ok(a);
+            }
+            _ => {}
+        }
+
+        if relate::structurally_relate_tys(self, a, b).is_err() {
+            let penalty = if a.is_numeric() && b.is_numeric() {
+                DIFFERENT_NUMERIC_TYPES
+            } else if wraps(a, b) || wraps(b, a) {
+                WRAPPED_TYPE
+            } else {
+                DIFFERENT_TYPES
+            };
+            self.penalize(penalty);
+        }
+        Ok(a)
+    }
+
+    fn regions(
+        &mut self,
+        a: ty::Region<'tcx>,
+        b: ty::Region<'tcx>,
+    ) -> RelateResult<'tcx, ty::Region<'tcx>> {
+        if a != b {
+            self.penalize(DIFFERENT_REGIONS);
+        }
+        Ok(a)
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        match (a.kind(), b.kind()) {
+            _ if a == b => {}
+            (ty::ConstKind::Infer(_) | ty::ConstKind::Error(_), _)
+            | (_, ty::ConstKind::Infer(_) | ty::ConstKind::Error(_)) => {}
+            _ => {
+                if relate::structurally_relate_consts(self, a, b).is_err() {
+                    self.penalize(DIFFERENT_CONSTS);
+                }
+            }
+        }
+        Ok(a)
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        Ok(a.rebind(self.relate(a.skip_binder(), b.skip_binder())?))
+    }
+}
@@ -0,0 +1,83 @@
+//! Debug validation that relating types for equality agrees with their stable hashes.
+//!
+//! Incremental compilation relies on two types hashing the same exactly if
+//! they are the same type. If `HashStable` and `Relate` disagree, e.g. because
+//! one of them ignores a field the other one looks at, we end up with
+//! incremental compilation bugs which are very hard to track down.
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::RelateResult;
+use crate::ty::{Ty, TyCtxt, TypeVisitableExt};
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::stable_hasher::{HashStable, StableHasher};
+use std::cell::Cell;
+
+/// Only every `SAMPLE_RATE`th pair of types is checked, as computing stable
+/// hashes is far more expensive than relating.
+const SAMPLE_RATE: usize = 64;
+
+thread_local! {
+    static SAMPLE_COUNTER: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Checks that `a` and `b` have the same stable hash, modulo regions, if and
+/// only if `result`, the result of equating them, is successful.
+///
+/// This does nothing unless the compiler has been built with debug assertions.
+/// Types containing inference variables, aliases, or errors are ignored, as
+/// equating them may succeed without them being structurally equal.
+pub fn debug_check_stable_hash_consistency<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    a: Ty<'tcx>,
+    b: Ty<'tcx>,
+    result: &RelateResult<'tcx, Ty<'tcx>>,
+) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    let related = match result {
+        Ok(_) => true,
+        // Hitting the depth limit says nothing about whether the types are equal.
+        Err(TypeError::RelateDepthLimitReached(_)) => return,
+        Err(_) => false,
+    };
+
+    let count = SAMPLE_COUNTER.get();
+    SAMPLE_COUNTER.set(count.wrapping_add(1));
+    if count % SAMPLE_RATE != 0 {
+        return;
+    }
+
+    if (a, b).has_infer() || (a, b).has_aliases() || (a, b).references_error() {
+        return;
+    }
+
+    // Const expressions may be related modulo algebraic identities, in which
+    // case related types don't have to hash the same.
+    if tcx.features().generic_const_exprs {
+        return;
+    }
+
+    let (a, b) = (tcx.erase_regions(a), tcx.erase_regions(b));
+    let (a_hash, b_hash) = tcx.with_stable_hashing_context(|mut hcx| {
+        let mut hash = |ty: Ty<'tcx>| {
+            let mut hasher = StableHasher::new();
+            ty.hash_stable(&mut hcx, &mut hasher);
+            hasher.finish::<Fingerprint>()
+        };
+        (hash(a), hash(b))
+    });
+
+    match (related, a_hash == b_hash) {
+        (true, false) => bug!(
+            "types related as equal but have different stable hashes: `{a:?}` ({a_hash:?}) \
+            and `{b:?}` ({b_hash:?})"
+        ),
+        (false, true) => bug!(
+            "types with the same stable hash ({a_hash:?}) failed to relate as equal: \
+            `{a:?}` and `{b:?}`"
+        ),
+        (true, true) | (false, false) => {}
+    }
+}
@@ -0,0 +1,85 @@
+//! Statistics about type relations, for compile-time triage.
+//!
+//! With `-Zrelate-stats`, the relation machinery counts how many values each
+//! kind of relation related, how many of them failed with which error, how
+//! deeply nested the related values got, and how much time was spent relating
+//! the predicates of trait objects. The aggregate is stored in the session's
+//! [`CodeStats`](rustc_session::code_stats::CodeStats) and printed at the end
+//! of the compilation. Without the flag, recording is a single branch.
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::RelateResult;
+use crate::ty::TyCtxt;
+use std::cell::Cell;
+use std::time::Instant;
+
+thread_local! {
+    /// Whether the predicates of a trait object are currently being related
+    /// on this thread, so that nested trait objects aren't timed twice.
+    static RELATING_EXISTENTIAL_PREDICATES: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Records the outcome of a relation of kind `tag`, if `-Zrelate-stats` is
+/// enabled.
+#[inline]
+pub(super) fn record_relate_result<'tcx, T>(
+    tcx: TyCtxt<'tcx>,
+    tag: &'static str,
+    result: &RelateResult<'tcx, T>,
+) {
+    if tcx.sess.opts.unstable_opts.relate_stats {
+        record_relate_result_cold(tcx, tag, result.as_ref().err());
+    }
+}
+
+#[cold]
+fn record_relate_result_cold<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    tag: &'static str,
+    error: Option<&TypeError<'tcx>>,
+) {
+    // `TypeError` has no names for its variants, so use the start of its
+    // `Debug` output, e.g. `Sorts` for `Sorts(ExpectedFound { .. })`.
+    let error = error.map(|error| {
+        let mut name = format!("{error:?}");
+        name.truncate(name.find(|c: char| !c.is_alphanumeric()).unwrap_or(name.len()));
+        name
+    });
+    tcx.sess.code_stats.record_relate_result(tag, error.as_deref());
+}
+
+/// Records that `depth` nested values are being related at once, if
+/// `-Zrelate-stats` is enabled.
+#[inline]
+pub(super) fn record_relate_depth(tcx: TyCtxt<'_>, depth: usize) {
+    if tcx.sess.opts.unstable_opts.relate_stats {
+        tcx.sess.code_stats.record_relate_depth(depth);
+    }
+}
+
+/// Measures the time spent relating the predicates of a trait object for as
+/// long as it is alive, if `-Zrelate-stats` is enabled.
+pub(super) struct ExistentialPredicatesTimer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    start: Option<Instant>,
+}
+
+impl<'tcx> ExistentialPredicatesTimer<'tcx> {
+    #[inline]
+    pub(super) fn start(tcx: TyCtxt<'tcx>) -> ExistentialPredicatesTimer<'tcx> {
+        let start = (tcx.sess.opts.unstable_opts.relate_stats
+            && !RELATING_EXISTENTIAL_PREDICATES.replace(true))
+        .then(Instant::now);
+        ExistentialPredicatesTimer { tcx, start }
+    }
+}
+
+impl Drop for ExistentialPredicatesTimer<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            RELATING_EXISTENTIAL_PREDICATES.set(false);
+            self.tcx.sess.code_stats.record_existential_predicates_time(start.elapsed());
+        }
+    }
+}
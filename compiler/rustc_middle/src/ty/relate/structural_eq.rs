@@ -0,0 +1,110 @@
+//! Syntactic equality of values, implemented as a type relation.
+//!
+//! Comparing values with `==` is usually all that's needed, but some checks
+//! only care about part of a value, e.g. about the arguments of a trait ref
+//! other than its self type. Relating these parts with [`StructuralEq`] makes
+//! sure that binders and nested values are treated consistently.
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::{self, Relate, RelateResult, TypeRelation};
+use crate::ty::{self, Ty, TyCtxt};
+
+/// A relation which only relates values if they are structurally equal.
+///
+/// Unlike type equality in the type system, this does not unify inference
+/// variables, normalize aliases, or treat binders up to renaming. Callers
+/// should anonymize bound variables before relating values containing
+/// binders, see [`TyCtxt::anonymize_bound_vars`].
+pub struct StructuralEq<'tcx> {
+    tcx: TyCtxt<'tcx>,
+}
+
+impl<'tcx> StructuralEq<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> StructuralEq<'tcx> {
+        StructuralEq { tcx }
+    }
+}
+
+impl<'tcx> TypeRelation<'tcx> for StructuralEq<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "StructuralEq"
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        _: ty::Variance,
+        _: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        self.relate(a, b)
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        if a == b {
+            return Ok(a);
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::Infer(_) | ty::Bound(..), _) | (_, ty::Infer(_) | ty::Bound(..)) => {
+                Err(TypeError::Sorts(relate::expected_found(a, b)))
+            }
+            _ => relate::structurally_relate_tys(self, a, b),
+        }
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        if a == b {
+            return Ok(a);
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::ConstKind::Infer(_), _) | (_, ty::ConstKind::Infer(_)) => {
+                Err(TypeError::ConstMismatch(relate::expected_found(a, b)))
+            }
+            _ => relate::structurally_relate_consts(self, a, b),
+        }
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        if a.bound_vars() != b.bound_vars() {
+            return Err(TypeError::Mismatch);
+        }
+        Ok(a.rebind(self.relate(a.skip_binder(), b.skip_binder())?))
+    }
+}
+
+/// Returns whether `a` and `b` are instantiations of the same trait which only
+/// differ in their self types.
+///
+/// Bound variables are compared up to renaming.
+pub fn trait_refs_eq_ignoring_self<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    a: ty::PolyTraitRef<'tcx>,
+    b: ty::PolyTraitRef<'tcx>,
+) -> bool {
+    if a.def_id() != b.def_id() {
+        return false;
+    }
+
+    let non_self_args =
+        |trait_ref: ty::PolyTraitRef<'tcx>| trait_ref.map_bound(|tr| tcx.mk_args(&tr.args[1..]));
+    let a = tcx.anonymize_bound_vars(non_self_args(a));
+    let b = tcx.anonymize_bound_vars(non_self_args(b));
+    StructuralEq::new(tcx).relate(a, b).is_ok()
+}
@@ -0,0 +1,69 @@
+//! Checking that relations behave like the relations they claim to be.
+//!
+//! A relation which isn't reflexive, or an equality relation which isn't
+//! symmetric, causes bugs which surface far away from their cause. With
+//! `-Zvalidate-relate`, compilers built with debug assertions check after
+//! each successful `relate(a, b)` that relating
+//!
+//! - `a` to `a` and `b` to `b` succeeds,
+//! - `b` to `a` succeeds, if the relation relates for equality, see
+//!   [`TypeRelation::relates_for_equality`],
+//! - the returned value to `a` and to `b` succeeds.
+//!
+//! The checks run inside of [`TypeRelation::validation_probe`], so relations
+//! whose side effects can't be rolled back aren't validated.
+
+use crate::ty::relate::{Relate, RelateResult, TypeRelation};
+use std::cell::Cell;
+
+thread_local! {
+    /// Whether a relation is being validated on this thread, in which case the
+    /// values related during the validation aren't validated themselves.
+    static VALIDATING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Validates the relation of `a` and `b`, if `-Zvalidate-relate` is enabled,
+/// see the module docs.
+#[inline]
+pub(super) fn validate_relate<'tcx, R: TypeRelation<'tcx>, T: Relate<'tcx>>(
+    relation: &mut R,
+    a: T,
+    b: T,
+    result: &RelateResult<'tcx, T>,
+) {
+    if cfg!(debug_assertions)
+        && relation.tcx().sess.opts.unstable_opts.validate_relate
+        && let Ok(value) = *result
+        && !VALIDATING.get()
+    {
+        validate_relate_cold(relation, a, b, value);
+    }
+}
+
+#[cold]
+fn validate_relate_cold<'tcx, R: TypeRelation<'tcx>, T: Relate<'tcx>>(
+    relation: &mut R,
+    a: T,
+    b: T,
+    value: T,
+) {
+    VALIDATING.set(true);
+    let _reset = rustc_data_structures::defer(|| VALIDATING.set(false));
+
+    let mut checks = vec![(a, a), (b, b), (value, a), (value, b)];
+    if relation.relates_for_equality() {
+        checks.push((b, a));
+    }
+    for (x, y) in checks {
+        let Some(result) = relation.validation_probe(|relation| relation.relate(x, y)) else {
+            return;
+        };
+        if let Err(err) = result {
+            relation.tcx().dcx().delayed_bug(format!(
+                "{}: relating `{a:?}` and `{b:?}` succeeded, \
+                but relating `{x:?}` and `{y:?}` failed with `{err:?}`",
+                relation.tag()
+            ));
+        }
+    }
+}
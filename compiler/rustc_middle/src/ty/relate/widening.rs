@@ -0,0 +1,173 @@
+//! Relating types up to lossless numeric widening.
+//!
+//! Lints such as `cast_lossless` want to know whether one type is the same
+//! as another, except for numeric types which can be converted into wider
+//! ones without losing information, e.g. `(u8, &f32)` and `(u32, &f64)`.
+
+use crate::ty::error::TypeError;
+use crate::ty::relate::{self, Relate, RelateResult, TypeRelation};
+use crate::ty::{self, FloatTy, IntTy, Ty, TyCtxt, UintTy};
+
+/// A numeric type which has been widened into another one while relating two
+/// values with [`LosslessWidening`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct NumericWidening<'tcx> {
+    pub from: Ty<'tcx>,
+    pub to: Ty<'tcx>,
+}
+
+/// A relation which relates `a` to `b` if they are equal, except for numeric
+/// types in `a` which can be losslessly widened into the corresponding types
+/// in `b`.
+///
+/// Widening is directional and follows variance: in contravariant positions,
+/// e.g. the inputs of function pointers, the types of `b` have to widen into
+/// those of `a`, while in invariant positions, e.g. behind `&mut`, no widening
+/// is allowed. Regions are ignored.
+///
+/// `usize` and `isize` are never widened, as their width depends on the target.
+pub struct LosslessWidening<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    ambient_variance: ty::Variance,
+    widenings: Vec<NumericWidening<'tcx>>,
+}
+
+impl<'tcx> LosslessWidening<'tcx> {
+    pub fn new(tcx: TyCtxt<'tcx>) -> LosslessWidening<'tcx> {
+        LosslessWidening { tcx, ambient_variance: ty::Covariant, widenings: Vec::new() }
+    }
+
+    /// Relates `a` and `b`, returning `b` together with the widenings needed
+    /// to get there from `a`, in the order they were encountered.
+    pub fn relate_widening<T: Relate<'tcx>>(
+        mut self,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, (T, Vec<NumericWidening<'tcx>>)> {
+        let value = self.relate(a, b)?;
+        Ok((value, self.widenings))
+    }
+}
+
+/// Returns the number of value bits of an integer type, i.e. its width
+/// without the sign bit, and whether it is signed.
+fn int_bits(ty: Ty<'_>) -> Option<(u64, bool)> {
+    match *ty.kind() {
+        ty::Int(IntTy::Isize) | ty::Uint(UintTy::Usize) => None,
+        ty::Int(int_ty) => Some((int_ty.bit_width()? - 1, true)),
+        ty::Uint(uint_ty) => Some((uint_ty.bit_width()?, false)),
+        _ => None,
+    }
+}
+
+/// Returns whether every value of `from` can be represented exactly by `to`.
+pub fn is_lossless_widening<'tcx>(from: Ty<'tcx>, to: Ty<'tcx>) -> bool {
+    match (from.kind(), to.kind()) {
+        (ty::Float(from), ty::Float(to)) => from.bit_width() < to.bit_width(),
+        (ty::Int(_) | ty::Uint(_), ty::Int(_) | ty::Uint(_)) => {
+            match (int_bits(from), int_bits(to)) {
+                // Unsigned types can't hold negative numbers.
+                (Some((_, true)), Some((_, false))) => false,
+                (Some((from, _)), Some((to, _))) => from < to,
+                _ => false,
+            }
+        }
+        (ty::Int(_) | ty::Uint(_), &ty::Float(to)) => {
+            let mantissa_digits = match to {
+                FloatTy::F16 => 11,
+                FloatTy::F32 => 24,
+                FloatTy::F64 => 53,
+                FloatTy::F128 => 113,
+            };
+            int_bits(from).is_some_and(|(bits, _)| bits <= mantissa_digits)
+        }
+        _ => false,
+    }
+}
+
+impl<'tcx> TypeRelation<'tcx> for LosslessWidening<'tcx> {
+    fn tcx(&self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn tag(&self) -> &'static str {
+        "LosslessWidening"
+    }
+
+    fn relate_with_variance<T: Relate<'tcx>>(
+        &mut self,
+        variance: ty::Variance,
+        _: ty::VarianceDiagInfo<'tcx>,
+        a: T,
+        b: T,
+    ) -> RelateResult<'tcx, T> {
+        let old_ambient_variance = self.ambient_variance;
+        self.ambient_variance = self.ambient_variance.xform(variance);
+        let r = self.relate(a, b);
+        self.ambient_variance = old_ambient_variance;
+        r
+    }
+
+    fn tys(&mut self, a: Ty<'tcx>, b: Ty<'tcx>) -> RelateResult<'tcx, Ty<'tcx>> {
+        if a == b {
+            return Ok(a);
+        }
+
+        let (from, to) = match self.ambient_variance {
+            ty::Covariant => (a, b),
+            ty::Contravariant => (b, a),
+            ty::Invariant | ty::Bivariant => (a, b),
+        };
+        if from.is_numeric() && to.is_numeric() {
+            return if self.ambient_variance != ty::Invariant && is_lossless_widening(from, to) {
+                self.widenings.push(NumericWidening { from, to });
+                Ok(b)
+            } else {
+                Err(TypeError::Sorts(relate::expected_found(a, b)))
+            };
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::Infer(_), _) | (_, ty::Infer(_)) => {
+                Err(TypeError::Sorts(relate::expected_found(a, b)))
+            }
+            _ => relate::structurally_relate_tys(self, a, b),
+        }
+    }
+
+    fn regions(
+        &mut self,
+        _: ty::Region<'tcx>,
+        b: ty::Region<'tcx>,
+    ) -> RelateResult<'tcx, ty::Region<'tcx>> {
+        Ok(b)
+    }
+
+    fn consts(
+        &mut self,
+        a: ty::Const<'tcx>,
+        b: ty::Const<'tcx>,
+    ) -> RelateResult<'tcx, ty::Const<'tcx>> {
+        if a == b {
+            return Ok(a);
+        }
+
+        match (a.kind(), b.kind()) {
+            (ty::ConstKind::Infer(_), _) | (_, ty::ConstKind::Infer(_)) => {
+                Err(TypeError::ConstMismatch(relate::expected_found(a, b)))
+            }
+            _ => relate::structurally_relate_consts(self, a, b),
+        }
+    }
+
+    fn binders<T>(
+        &mut self,
+        a: ty::Binder<'tcx, T>,
+        b: ty::Binder<'tcx, T>,
+    ) -> RelateResult<'tcx, ty::Binder<'tcx, T>>
+    where
+        T: Relate<'tcx>,
+    {
+        Ok(b.rebind(self.relate(a.skip_binder(), b.skip_binder())?))
+    }
+}
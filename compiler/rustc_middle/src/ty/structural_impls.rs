@@ -304,6 +304,7 @@ fn fmt<Infcx: InferCtxtLike<Interner = TyCtxt<'tcx>>>(
     ::rustc_hir::def::Res,
     ::rustc_hir::def_id::LocalDefId,
     ::rustc_hir::ByRef,
+    ::rustc_hir::Constness,
     ::rustc_hir::HirId,
     ::rustc_hir::MatchSource,
     ::rustc_target::asm::InlineAsmRegOrRegClass,
@@ -322,6 +323,7 @@ fn fmt<Infcx: InferCtxtLike<Interner = TyCtxt<'tcx>>>(
     // really meant to be folded. In general, we can only fold a fully
     // general `Region`.
     crate::ty::BoundRegionKind,
+    crate::ty::BoundVariableKind,
     crate::ty::AssocItem,
     crate::ty::AssocKind,
     crate::ty::AliasTyKind,
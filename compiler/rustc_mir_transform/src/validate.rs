@@ -9,6 +9,7 @@
 use rustc_middle::mir::visit::{NonUseContext, PlaceContext, Visitor};
 use rustc_middle::mir::*;
 use rustc_middle::ty::adjustment::PointerCoercion;
+use rustc_middle::ty::error::TypeError;
 use rustc_middle::ty::{
     self, CoroutineArgsExt, InstanceDef, ParamEnv, Ty, TyCtxt, TypeVisitableExt, Variance,
 };
@@ -18,7 +19,7 @@
 
 use crate::util::is_within_packed;
 
-use crate::util::relate_types;
+use crate::util::try_relate_types;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum EdgeKind {
@@ -573,13 +574,20 @@ fn fail(&mut self, location: Location, msg: impl Into<String>) {
         self.failures.push((location, msg.into()));
     }
 
+    /// Records a failure caused by relating two types, including the reason
+    /// they didn't relate.
+    fn fail_relate(&mut self, location: Location, msg: impl Into<String>, err: TypeError<'tcx>) {
+        let msg = format!("{}: {}", msg.into(), err.to_string(self.tcx));
+        self.fail(location, msg);
+    }
+
     /// Check if src can be assigned into dest.
     /// This is not precise, it will accept some incorrect assignments.
-    fn mir_assign_valid_types(&self, src: Ty<'tcx>, dest: Ty<'tcx>) -> bool {
+    fn mir_assign_valid_types(&self, src: Ty<'tcx>, dest: Ty<'tcx>) -> Result<(), TypeError<'tcx>> {
         // Fast path before we normalize.
         if src == dest {
             // Equal types, all is good.
-            return true;
+            return Ok(());
         }
 
         // We sometimes have to use `defining_opaque_types` for subtyping
@@ -588,7 +596,7 @@ fn mir_assign_valid_types(&self, src: Ty<'tcx>, dest: Ty<'tcx>) -> bool {
         // in that case. We still check this after analysis as all opaque
         // types have been revealed at this point.
         if (src, dest).has_opaque_types() {
-            return true;
+            return Ok(());
         }
 
         // After borrowck subtyping should be fully explicit via
@@ -599,7 +607,7 @@ fn mir_assign_valid_types(&self, src: Ty<'tcx>, dest: Ty<'tcx>) -> bool {
             Variance::Covariant
         };
 
-        crate::util::relate_types(self.tcx, self.param_env, variance, src, dest)
+        try_relate_types(self.tcx, self.param_env, variance, src, dest)
     }
 }
 
@@ -662,12 +670,13 @@ fn visit_projection_elem(
                     this.fail(location, format!("Out of bounds field {f:?} for {parent_ty:?}"));
                 };
                 let check_equal = |this: &mut Self, location, f_ty| {
-                    if !this.mir_assign_valid_types(ty, f_ty) {
-                        this.fail(
+                    if let Err(err) = this.mir_assign_valid_types(ty, f_ty) {
+                        this.fail_relate(
                             location,
                             format!(
                                 "Field projection `{place_ref:?}.{f:?}` specified type `{ty:?}`, but actual type is `{f_ty:?}`"
-                            )
+                            ),
+                            err,
                         )
                     }
                 };
@@ -776,19 +785,14 @@ fn visit_projection_elem(
                 }
             }
             ProjectionElem::Subtype(ty) => {
-                if !relate_types(
-                    self.tcx,
-                    self.param_env,
-                    Variance::Covariant,
-                    ty,
-                    place_ref.ty(&self.body.local_decls, self.tcx).ty,
-                ) {
-                    self.fail(
+                let place_ty = place_ref.ty(&self.body.local_decls, self.tcx).ty;
+                if let Err(err) =
+                    try_relate_types(self.tcx, self.param_env, Variance::Covariant, ty, place_ty)
+                {
+                    self.fail_relate(
                         location,
-                        format!(
-                            "Failed subtyping {ty:#?} and {:#?}",
-                            place_ref.ty(&self.body.local_decls, self.tcx).ty
-                        ),
+                        format!("Failed subtyping {ty:#?} and {place_ty:#?}"),
+                        err,
                     )
                 }
             }
@@ -882,8 +886,10 @@ macro_rules! check_kinds {
                 AggregateKind::Tuple => {}
                 AggregateKind::Array(dest) => {
                     for src in fields {
-                        if !self.mir_assign_valid_types(src.ty(self.body, self.tcx), dest) {
-                            self.fail(location, "array field has the wrong type");
+                        if let Err(err) =
+                            self.mir_assign_valid_types(src.ty(self.body, self.tcx), dest)
+                        {
+                            self.fail_relate(location, "array field has the wrong type", err);
                         }
                     }
                 }
@@ -897,8 +903,8 @@ macro_rules! check_kinds {
                     );
                     if fields.len() == 1 {
                         let src_ty = fields.raw[0].ty(self.body, self.tcx);
-                        if !self.mir_assign_valid_types(src_ty, dest_ty) {
-                            self.fail(location, "union field has the wrong type");
+                        if let Err(err) = self.mir_assign_valid_types(src_ty, dest_ty) {
+                            self.fail_relate(location, "union field has the wrong type", err);
                         }
                     } else {
                         self.fail(location, "unions should have one initialized field");
@@ -915,8 +921,10 @@ macro_rules! check_kinds {
                         let dest_ty = self
                             .tcx
                             .normalize_erasing_regions(self.param_env, dest.ty(self.tcx, args));
-                        if !self.mir_assign_valid_types(src.ty(self.body, self.tcx), dest_ty) {
-                            self.fail(location, "adt field has the wrong type");
+                        if let Err(err) =
+                            self.mir_assign_valid_types(src.ty(self.body, self.tcx), dest_ty)
+                        {
+                            self.fail_relate(location, "adt field has the wrong type", err);
                         }
                     }
                 }
@@ -926,8 +934,10 @@ macro_rules! check_kinds {
                         self.fail(location, "closure has the wrong number of initialized fields");
                     }
                     for (src, dest) in std::iter::zip(fields, upvars) {
-                        if !self.mir_assign_valid_types(src.ty(self.body, self.tcx), dest) {
-                            self.fail(location, "closure field has the wrong type");
+                        if let Err(err) =
+                            self.mir_assign_valid_types(src.ty(self.body, self.tcx), dest)
+                        {
+                            self.fail_relate(location, "closure field has the wrong type", err);
                         }
                     }
                 }
@@ -937,8 +947,10 @@ macro_rules! check_kinds {
                         self.fail(location, "coroutine has the wrong number of initialized fields");
                     }
                     for (src, dest) in std::iter::zip(fields, upvars) {
-                        if !self.mir_assign_valid_types(src.ty(self.body, self.tcx), dest) {
-                            self.fail(location, "coroutine field has the wrong type");
+                        if let Err(err) =
+                            self.mir_assign_valid_types(src.ty(self.body, self.tcx), dest)
+                        {
+                            self.fail_relate(location, "coroutine field has the wrong type", err);
                         }
                     }
                 }
@@ -951,8 +963,11 @@ macro_rules! check_kinds {
                         );
                     }
                     for (src, dest) in std::iter::zip(fields, upvars) {
-                        if !self.mir_assign_valid_types(src.ty(self.body, self.tcx), dest) {
-                            self.fail(location, "coroutine-closure field has the wrong type");
+                        if let Err(err) =
+                            self.mir_assign_valid_types(src.ty(self.body, self.tcx), dest)
+                        {
+                            let msg = "coroutine-closure field has the wrong type";
+                            self.fail_relate(location, msg, err);
                         }
                     }
                 }
@@ -987,8 +1002,10 @@ macro_rules! check_kinds {
 
                         // FIXME: Check metadata more generally
                         if pointee_ty.is_slice() {
-                            if !self.mir_assign_valid_types(metadata_ty, self.tcx.types.usize) {
-                                self.fail(location, "slice metadata must be usize");
+                            if let Err(err) =
+                                self.mir_assign_valid_types(metadata_ty, self.tcx.types.usize)
+                            {
+                                self.fail_relate(location, "slice metadata must be usize", err);
                             }
                         } else if pointee_ty.is_sized(self.tcx, self.param_env) {
                             if metadata_ty != self.tcx.types.unit {
@@ -1022,10 +1039,11 @@ macro_rules! check_kinds {
                 if crate::util::binop_right_homogeneous(*op) {
                     if let Eq | Lt | Le | Ne | Ge | Gt = op {
                         // The function pointer types can have lifetimes
-                        if !self.mir_assign_valid_types(a, b) {
-                            self.fail(
+                        if let Err(err) = self.mir_assign_valid_types(a, b) {
+                            self.fail_relate(
                                 location,
                                 format!("Cannot {op:?} compare incompatible types {a:?} and {b:?}"),
+                                err,
                             );
                         }
                     } else if a != b {
@@ -1345,14 +1363,18 @@ fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
                 let left_ty = dest.ty(&self.body.local_decls, self.tcx).ty;
                 let right_ty = rvalue.ty(&self.body.local_decls, self.tcx);
 
-                if !self.mir_assign_valid_types(right_ty, left_ty) {
+                if let Err(err) = self.mir_assign_valid_types(right_ty, left_ty) {
                     self.fail(
                         location,
                         format!(
                             "encountered `{:?}` with incompatible types:\n\
                             left-hand side has type: {}\n\
-                            right-hand side has type: {}",
-                            statement.kind, left_ty, right_ty,
+                            right-hand side has type: {}\n\
+                            reason: {}",
+                            statement.kind,
+                            left_ty,
+                            right_ty,
+                            err.to_string(self.tcx),
                         ),
                     );
                 }
@@ -1415,8 +1437,9 @@ fn visit_statement(&mut self, statement: &Statement<'tcx>, location: Location) {
                 };
                 // since CopyNonOverlapping is parametrized by 1 type,
                 // we only need to check that they are equal and not keep an extra parameter.
-                if !self.mir_assign_valid_types(op_src_ty, op_dst_ty) {
-                    self.fail(location, format!("bad arg ({op_src_ty:?} != {op_dst_ty:?})"));
+                if let Err(err) = self.mir_assign_valid_types(op_src_ty, op_dst_ty) {
+                    let msg = format!("bad arg ({op_src_ty:?} != {op_dst_ty:?})");
+                    self.fail_relate(location, msg, err);
                 }
 
                 let op_cnt_ty = count.ty(&self.body.local_decls, self.tcx);
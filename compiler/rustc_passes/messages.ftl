@@ -591,6 +591,18 @@ passes_pass_by_value =
 
 passes_proc_macro_bad_sig = {$kind} has incorrect signature
 
+passes_relate_test_invalid_attribute =
+    `#[rustc_relate_test]` can only be applied to type aliases of pairs of types and functions with two where clauses
+
+passes_relate_test_result =
+    {$kind}({$a}, {$b}) = {$result}
+
+passes_relate_test_unknown_kind =
+    unknown relation `{$name}`, expected one of `eq`, `sub`, `lub`, `glb`, `widen`, `diff`, `walk`, `matches`, or `similarity`
+
+passes_relate_test_unsupported_kind =
+    relation `{$name}` can't relate where clauses, expected `eq` or `matches`
+
 passes_repr_conflicting =
     conflicting representation hints
 
@@ -955,6 +955,40 @@ pub struct LayoutInvalidAttribute {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(passes_relate_test_result)]
+pub struct RelateTestResult {
+    #[primary_span]
+    pub span: Span,
+    pub kind: Symbol,
+    pub a: String,
+    pub b: String,
+    pub result: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_relate_test_invalid_attribute)]
+pub struct RelateTestInvalidAttribute {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_relate_test_unknown_kind)]
+pub struct RelateTestUnknownKind {
+    #[primary_span]
+    pub span: Span,
+    pub name: Symbol,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_relate_test_unsupported_kind)]
+pub struct RelateTestUnsupportedKind {
+    #[primary_span]
+    pub span: Span,
+    pub name: Symbol,
+}
+
 #[derive(Diagnostic)]
 #[diag(passes_abi_of)]
 pub struct AbiOf {
@@ -32,6 +32,7 @@
 pub mod loops;
 mod naked_functions;
 mod reachable;
+pub mod relate_test;
 pub mod stability;
 mod upvars;
 mod weak_lang_items;
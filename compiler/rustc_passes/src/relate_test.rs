@@ -0,0 +1,175 @@
+//! Implements `#[rustc_relate_test(kind)]`, which relates the two types of a
+//! type alias of a pair, e.g. `type Test = (A, B);`, and emits the result as
+//! an error. This allows UI tests to cover the behavior of the type relations,
+//! including which `TypeError` is returned, directly.
+//!
+//! Applied to a function with two where clauses, e.g.
+//! `fn test() where A: Trait, B: 'static {}`, the where clauses are related
+//! instead.
+
+use rustc_ast::Attribute;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::LocalDefId;
+use rustc_middle::ty::relate::{
+    diff_tys, pair_walk, similarity, LosslessWidening, Match, StructuralEq, TypeRelation,
+};
+use rustc_middle::ty::{self, Ty, TyCtxt};
+use rustc_span::symbol::{sym, Symbol};
+use rustc_span::Span;
+use rustc_trait_selection::infer::{DefineOpaqueTypes, InferOk, TyCtxtInferExt};
+use rustc_trait_selection::traits::ObligationCause;
+
+use crate::errors::{
+    RelateTestInvalidAttribute, RelateTestResult, RelateTestUnknownKind, RelateTestUnsupportedKind,
+};
+
+pub fn test_relate(tcx: TyCtxt<'_>) {
+    if !tcx.features().rustc_attrs {
+        // if the `rustc_attrs` feature is not enabled, don't bother testing relations
+        return;
+    }
+    for id in tcx.hir_crate_items(()).definitions() {
+        for attr in tcx.get_attrs(id, sym::rustc_relate_test) {
+            let span = tcx.def_span(id);
+            if let DefKind::Fn = tcx.def_kind(id) {
+                relate_where_clauses(tcx, id, span, attr);
+                continue;
+            }
+            let pair = match tcx.def_kind(id) {
+                DefKind::TyAlias => match *tcx.type_of(id).instantiate_identity().kind() {
+                    ty::Tuple(tys) if tys.len() == 2 => Some((tys[0], tys[1])),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let Some((a, b)) = pair else {
+                tcx.dcx().emit_err(RelateTestInvalidAttribute { span });
+                continue;
+            };
+
+            for meta_item in attr.meta_item_list().unwrap_or_default() {
+                match meta_item.name_or_empty() {
+                    kind @ (sym::eq
+                    | sym::sub
+                    | sym::lub
+                    | sym::glb
+                    | sym::widen
+                    | sym::diff
+                    | sym::walk
+                    | sym::matches
+                    | sym::similarity) => {
+                        let result = relate(tcx, id, span, kind, a, b);
+                        tcx.dcx().emit_err(RelateTestResult {
+                            span,
+                            kind,
+                            a: a.to_string(),
+                            b: b.to_string(),
+                            result,
+                        });
+                    }
+                    name => {
+                        tcx.dcx().emit_err(RelateTestUnknownKind { span: meta_item.span(), name });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Relates the two where clauses of the function `def_id`. Only the relations
+/// which don't need an inference context, `eq` and `matches`, are supported.
+fn relate_where_clauses(tcx: TyCtxt<'_>, def_id: LocalDefId, span: Span, attr: &Attribute) {
+    let &[(a, _), (b, _)] = tcx.explicit_predicates_of(def_id).predicates else {
+        tcx.dcx().emit_err(RelateTestInvalidAttribute { span });
+        return;
+    };
+    for meta_item in attr.meta_item_list().unwrap_or_default() {
+        let result = match meta_item.name_or_empty() {
+            sym::eq => StructuralEq::new(tcx).relate(a, b).map(drop),
+            sym::matches => Match::new(tcx).match_value(a, b).map(drop),
+            name => {
+                tcx.dcx().emit_err(RelateTestUnsupportedKind { span: meta_item.span(), name });
+                continue;
+            }
+        };
+        let result = match result {
+            Ok(()) => "Ok(())".to_string(),
+            Err(err) => format!("Err({})", err.to_string(tcx)),
+        };
+        tcx.dcx().emit_err(RelateTestResult {
+            span,
+            kind: meta_item.name_or_empty(),
+            a: a.to_string(),
+            b: b.to_string(),
+            result,
+        });
+    }
+}
+
+/// Relates `a` and `b` using the relation `kind`, returning the result as a string.
+fn relate<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+    span: Span,
+    kind: Symbol,
+    a: Ty<'tcx>,
+    b: Ty<'tcx>,
+) -> String {
+    if kind == sym::widen {
+        return match LosslessWidening::new(tcx).relate_widening(a, b) {
+            Ok((ty, widenings)) => {
+                let widenings: Vec<_> =
+                    widenings.iter().map(|w| format!("{} -> {}", w.from, w.to)).collect();
+                format!("Ok({ty}, [{}])", widenings.join(", "))
+            }
+            Err(err) => format!("Err({err:?})"),
+        };
+    }
+    if kind == sym::diff {
+        let Some(diff) = diff_tys(tcx, a, b) else {
+            return "None".to_string();
+        };
+        let leaves: Vec<_> = diff
+            .leaves()
+            .into_iter()
+            .map(|leaf| format!("{:?}: `{}` != `{}`", leaf.path, leaf.expected, leaf.found))
+            .collect();
+        return format!("Some([{}])", leaves.join(", "));
+    }
+    if kind == sym::walk {
+        let pairs: Vec<_> = pair_walk(tcx, a, b)
+            .map(|pair| format!("{:?} {:?} `{}` ~ `{}`", pair.path, pair.variance, pair.a, pair.b))
+            .collect();
+        return format!("[{}]", pairs.join(", "));
+    }
+    if kind == sym::matches {
+        return match Match::new(tcx).match_value(a, b) {
+            Ok(bindings) => {
+                let bindings: Vec<_> =
+                    bindings.iter().map(|(hole, value)| format!("{hole} => {value}")).collect();
+                format!("Ok([{}])", bindings.join(", "))
+            }
+            Err(err) => format!("Err({err:?})"),
+        };
+    }
+    if kind == sym::similarity {
+        return similarity(tcx, a, b).to_string();
+    }
+
+    let param_env = tcx.param_env(def_id);
+    let infcx = tcx.infer_ctxt().build();
+    let cause = ObligationCause::misc(span, def_id);
+    let at = infcx.at(&cause, param_env);
+    let result = match kind {
+        sym::eq => at.eq(DefineOpaqueTypes::No, a, b).map(|InferOk { value: (), .. }| None),
+        sym::sub => at.sub(DefineOpaqueTypes::No, a, b).map(|InferOk { value: (), .. }| None),
+        sym::lub => at.lub(DefineOpaqueTypes::No, a, b).map(|InferOk { value, .. }| Some(value)),
+        sym::glb => at.glb(DefineOpaqueTypes::No, a, b).map(|InferOk { value, .. }| Some(value)),
+        _ => unreachable!(),
+    };
+    match result {
+        Ok(Some(ty)) => format!("Ok({})", infcx.resolve_vars_if_possible(ty)),
+        Ok(None) => "Ok(())".to_string(),
+        Err(err) => format!("Err({err:?})"),
+    }
+}
@@ -145,8 +145,9 @@ fn start_query<R>(
                 task_deps: current_icx.task_deps,
             };
 
-            // Use the `ImplicitCtxt` while we execute the query.
-            tls::enter_context(&new_icx, compute)
+            // Use the `ImplicitCtxt` while we execute the query. The query may
+            // be forced while relating types, which mustn't affect its result.
+            tls::enter_context(&new_icx, || ty::relate::with_fresh_relate_depth(compute))
         })
     }
 
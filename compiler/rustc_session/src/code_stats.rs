@@ -4,6 +4,7 @@
 use rustc_span::Symbol;
 use rustc_target::abi::{Align, Size};
 use std::cmp;
+use std::time::Duration;
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct VariantInfo {
@@ -89,10 +90,62 @@ pub struct VTableSizeInfo {
     pub upcasting_cost_percent: f64,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum RelateExtremeKind {
+    /// The number of nested values related at once.
+    Depth,
+    /// The number of components of a single value, e.g. the elements of a
+    /// tuple or the generic args of an ADT, related at once.
+    Width,
+}
+
+pub struct RelateExtremeInfo {
+    /// The depth or width that was reached.
+    pub size: usize,
+    /// The kind of relation, e.g. `"eq"`.
+    pub tag: &'static str,
+    /// The outermost values being related.
+    pub values: String,
+    /// The cause of the relation, if any.
+    pub cause: Option<String>,
+}
+
+#[derive(Default)]
+struct RelateExtremes {
+    deepest: Option<RelateExtremeInfo>,
+    widest: Option<RelateExtremeInfo>,
+}
+
+impl RelateExtremes {
+    fn get_mut(&mut self, kind: RelateExtremeKind) -> &mut Option<RelateExtremeInfo> {
+        match kind {
+            RelateExtremeKind::Depth => &mut self.deepest,
+            RelateExtremeKind::Width => &mut self.widest,
+        }
+    }
+}
+
+/// The statistics about type relations collected with `-Zrelate-stats`.
+#[derive(Default)]
+struct RelateStats {
+    /// The number of values related, per kind of relation, e.g. `"eq"`.
+    invocations: FxHashMap<&'static str, u64>,
+    /// The number of values which were related successfully.
+    successes: u64,
+    /// The number of values which failed to relate, per variant of the error.
+    failures: FxHashMap<String, u64>,
+    /// The maximum number of nested values related at once.
+    max_depth: usize,
+    /// The time spent relating the predicates of trait objects.
+    existential_predicates_time: Duration,
+}
+
 #[derive(Default)]
 pub struct CodeStats {
     type_sizes: Lock<FxHashSet<TypeSizeInfo>>,
     vtable_sizes: Lock<FxHashMap<DefId, VTableSizeInfo>>,
+    relate_extremes: Lock<RelateExtremes>,
+    relate_stats: Lock<RelateStats>,
 }
 
 impl CodeStats {
@@ -134,6 +187,84 @@ pub fn record_vtable_size(&self, trait_did: DefId, trait_name: &str, info: VTabl
         );
     }
 
+    /// Returns whether a type relation of the given `size` would be the deepest
+    /// or widest one so far, so that callers only have to describe it if so.
+    pub fn is_new_relate_extreme(&self, kind: RelateExtremeKind, size: usize) -> bool {
+        self.relate_extremes.lock().get_mut(kind).as_ref().map_or(true, |max| size > max.size)
+    }
+
+    pub fn record_relate_extreme(&self, kind: RelateExtremeKind, info: RelateExtremeInfo) {
+        let mut extremes = self.relate_extremes.lock();
+        let max = extremes.get_mut(kind);
+        if max.as_ref().map_or(true, |max| info.size > max.size) {
+            *max = Some(info);
+        }
+    }
+
+    pub fn print_relate_extremes(&self) {
+        let extremes = self.relate_extremes.lock();
+        for (name, info) in [("deepest", &extremes.deepest), ("widest", &extremes.widest)] {
+            let Some(RelateExtremeInfo { size, tag, values, cause }) = info else {
+                continue;
+            };
+            let cause = cause.as_ref().map(|cause| format!(" because of {cause}"));
+            println!(
+                "print-relate-extremes {name}: {size} [{tag}] relating `{values}`{}",
+                cause.unwrap_or_default()
+            );
+        }
+    }
+
+    /// Records that a relation of kind `tag` related two values, failing with
+    /// the error variant `error` if any.
+    pub fn record_relate_result(&self, tag: &'static str, error: Option<&str>) {
+        let mut stats = self.relate_stats.lock();
+        *stats.invocations.entry(tag).or_default() += 1;
+        match error {
+            Some(error) => match stats.failures.get_mut(error) {
+                Some(count) => *count += 1,
+                None => {
+                    stats.failures.insert(error.to_owned(), 1);
+                }
+            },
+            None => stats.successes += 1,
+        }
+    }
+
+    pub fn record_relate_depth(&self, depth: usize) {
+        let mut stats = self.relate_stats.lock();
+        stats.max_depth = cmp::max(stats.max_depth, depth);
+    }
+
+    pub fn record_existential_predicates_time(&self, time: Duration) {
+        self.relate_stats.lock().existential_predicates_time += time;
+    }
+
+    pub fn print_relate_stats(&self) {
+        let stats = self.relate_stats.lock();
+        // Sort by count, and by name for the same count, so that the output is
+        // deterministic.
+        fn sorted<K: Ord>(counts: &FxHashMap<K, u64>) -> Vec<(&K, u64)> {
+            #[allow(rustc::potential_query_instability)]
+            let mut sorted: Vec<_> = counts.iter().map(|(name, &count)| (name, count)).collect();
+            sorted.sort_by_key(|&(name, count)| (cmp::Reverse(count), name));
+            sorted
+        }
+
+        for (tag, count) in sorted(&stats.invocations) {
+            println!("relate-stats invocations [{tag}]: {count}");
+        }
+        println!("relate-stats successes: {}", stats.successes);
+        for (error, count) in sorted(&stats.failures) {
+            println!("relate-stats failures [{error}]: {count}");
+        }
+        println!("relate-stats max depth: {}", stats.max_depth);
+        println!(
+            "relate-stats existential predicates time: {:.3}ms",
+            stats.existential_predicates_time.as_secs_f64() * 1000.0
+        );
+    }
+
     pub fn print_type_sizes(&self) {
         let type_sizes = self.type_sizes.borrow();
         // We will soon sort, so the initial order does not matter.
@@ -1656,6 +1656,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         "output statistics about monomorphization collection"),
     dump_mono_stats_format: DumpMonoStatsFormat = (DumpMonoStatsFormat::Markdown, parse_dump_mono_stats, [UNTRACKED],
         "the format to use for -Z dump-mono-stats (`markdown` (default) or `json`)"),
+    dump_type_relations: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write a JSON line describing each top-level type relation to the given file \
+        (default: no)"),
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
     dylib_lto: bool = (false, parse_bool, [UNTRACKED],
@@ -1871,6 +1874,8 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         "print the result of the monomorphization collection pass. \
          Value `lazy` means to use normal collection; `eager` means to collect all items.
          Note that this overwrites the effect `-Clink-dead-code` has on collection!"),
+    print_relate_extremes: bool = (false, parse_bool, [UNTRACKED],
+        "print the deepest and the widest type relation of the compilation (default: no)"),
     print_type_sizes: bool = (false, parse_bool, [UNTRACKED],
         "print layout information for each type encountered (default: no)"),
     print_vtable_sizes: bool = (false, parse_bool, [UNTRACKED],
@@ -1895,6 +1900,11 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         "enable queries of the dependency graph for regression testing (default: no)"),
     randomize_layout: bool = (false, parse_bool, [TRACKED],
         "randomize the layout of types (default: no)"),
+    relate_depth_limit: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "the maximum depth to which nested types are related \
+        (default: 16 times the recursion limit)"),
+    relate_stats: bool = (false, parse_bool, [UNTRACKED],
+        "print statistics about the type relations of the compilation (default: no)"),
     relax_elf_relocations: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "whether ELF relocations can be relaxed"),
     remap_cwd_prefix: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
@@ -2061,6 +2071,9 @@ pub(crate) fn parse_wasm_c_abi(slot: &mut WasmCAbi, v: Option<&str>) -> bool {
         "Generate sync unwind tables instead of async unwind tables (default: no)"),
     validate_mir: bool = (false, parse_bool, [UNTRACKED],
         "validate MIR after each transformation"),
+    validate_relate: bool = (false, parse_bool, [UNTRACKED],
+        "check that type relations are reflexive and that equality relations are symmetric, \
+        if the compiler has been built with debug assertions (default: no)"),
     #[rustc_lint_opt_deny_field_access("use `Session::verbose_internals` instead of this field")]
     verbose_internals: bool = (false, parse_bool, [TRACKED_NO_CRATE_HASH],
         "in general, enable more debug printouts (default: no)"),
@@ -128,6 +128,8 @@ pub struct Limits {
     pub move_size_limit: Limit,
     /// The maximum length of types during monomorphization.
     pub type_length_limit: Limit,
+    /// The maximum depth to which nested types are structurally related.
+    pub relate_depth_limit: Limit,
 }
 
 pub struct CompilerIO {
@@ -708,6 +708,7 @@
         destructuring_assignment,
         diagnostic,
         diagnostic_namespace,
+        diff,
         direct,
         discriminant_kind,
         discriminant_type,
@@ -933,6 +934,7 @@
         generic_const_items,
         generic_param_attrs,
         get_context,
+        glb,
         global_alloc_ty,
         global_allocator,
         global_asm,
@@ -1109,6 +1111,7 @@
         loongarch_target_feature,
         loop_break_value,
         lt,
+        lub,
         macro_at_most_once_rep,
         macro_attributes_in_derive_output,
         macro_escape,
@@ -1130,6 +1133,7 @@
         masked,
         match_beginning_vert,
         match_default_bindings,
+        matches,
         matches_macro,
         maxnumf128,
         maxnumf16,
@@ -1639,6 +1643,7 @@
         rustc_promotable,
         rustc_reallocator,
         rustc_regions,
+        rustc_relate_test,
         rustc_reservation_impl,
         rustc_safe_intrinsic,
         rustc_serialize,
@@ -1751,6 +1756,7 @@
         simd_trunc,
         simd_with_exposed_provenance,
         simd_xor,
+        similarity,
         since,
         sinf128,
         sinf16,
@@ -2020,12 +2026,14 @@
         vreg_low16,
         vtable_align,
         vtable_size,
+        walk,
         warn,
         wasip2,
         wasm_abi,
         wasm_import_module,
         wasm_target_feature,
         while_let,
+        widen,
         windows,
         windows_subsystem,
         with_negative_coherence,
@@ -0,0 +1,138 @@
+//! Comparing the bounds of two trait definitions.
+//!
+//! Checking whether two traits or trait aliases are equivalent requires
+//! comparing their elaborated supertraits and where-clauses. Instead of
+//! comparing their pretty-printed forms, [`compare_trait_bounds`] relates
+//! them bound by bound and reports how each bound differs.
+
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty::error::TypeError;
+use rustc_middle::ty::relate::{RelateResult, StructuralEq, TypeRelation};
+use rustc_middle::ty::{self, GenericArgs, Ty, TyCtxt};
+
+use super::elaborate;
+
+/// How a single bound of one trait relates to the bounds of another, see
+/// [`compare_trait_bounds`].
+#[derive(Clone, Debug)]
+pub enum BoundVerdict<'tcx> {
+    /// Both traits have this bound.
+    Equal(ty::Clause<'tcx>),
+    /// Both traits have a bound on the same trait or associated item for the
+    /// same self type, but with different arguments.
+    Mismatch { a: ty::Clause<'tcx>, b: ty::Clause<'tcx>, err: TypeError<'tcx> },
+    /// Only the first trait has this bound.
+    OnlyInA(ty::Clause<'tcx>),
+    /// Only the second trait has this bound.
+    OnlyInB(ty::Clause<'tcx>),
+}
+
+impl<'tcx> BoundVerdict<'tcx> {
+    pub fn is_equal(&self) -> bool {
+        matches!(self, BoundVerdict::Equal(_))
+    }
+}
+
+/// Compares the elaborated supertraits and where-clauses of the traits or
+/// trait aliases `a` and `b`, returning a verdict for each of their bounds.
+///
+/// The generic parameters of `b` are identified with those of `a` by their
+/// position. Returns `None` if the two traits don't have the same kinds of
+/// generic parameters.
+pub fn compare_trait_bounds<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    a: DefId,
+    b: DefId,
+) -> Option<Vec<BoundVerdict<'tcx>>> {
+    let a_generics = tcx.generics_of(a);
+    let b_generics = tcx.generics_of(b);
+    if a_generics.parent.is_some()
+        || b_generics.parent.is_some()
+        || a_generics.own_params.len() != b_generics.own_params.len()
+        || std::iter::zip(&a_generics.own_params, &b_generics.own_params)
+            .any(|(a, b)| a.kind.to_ord() != b.kind.to_ord())
+    {
+        return None;
+    }
+
+    let args = GenericArgs::identity_for_item(tcx, a);
+    let bounds = |def_id: DefId| -> Vec<ty::Clause<'tcx>> {
+        let clauses = tcx.predicates_of(def_id).instantiate(tcx, args).predicates;
+        // Elaborating `Self: Trait` adds all of its bounds, but the trait itself
+        // is not one of them.
+        elaborate(tcx, clauses)
+            .filter(|clause| clause.as_trait_clause().map_or(true, |t| t.def_id() != def_id))
+            .collect()
+    };
+    let a_bounds = bounds(a);
+    let mut b_bounds: Vec<_> = bounds(b).into_iter().map(Some).collect();
+
+    let mut verdicts = Vec::with_capacity(a_bounds.len());
+    let mut unmatched = vec![];
+    for a_bound in a_bounds {
+        if let Some(b_bound) = b_bounds.iter_mut().find(|b_bound| **b_bound == Some(a_bound)) {
+            *b_bound = None;
+            verdicts.push(BoundVerdict::Equal(a_bound));
+        } else {
+            unmatched.push(a_bound);
+        }
+    }
+
+    // Bounds which aren't equal are paired with the first remaining bound of
+    // the other trait on the same item, if any.
+    for a_bound in unmatched {
+        let b_bound = bound_key(a_bound).and_then(|key| {
+            b_bounds
+                .iter_mut()
+                .find(|b_bound| b_bound.is_some_and(|b| bound_key(b) == Some(key)))
+                .and_then(Option::take)
+        });
+        verdicts.push(match b_bound {
+            Some(b_bound) => match relate_bounds(tcx, a_bound, b_bound) {
+                Ok(()) => BoundVerdict::Equal(a_bound),
+                Err(err) => BoundVerdict::Mismatch { a: a_bound, b: b_bound, err },
+            },
+            None => BoundVerdict::OnlyInA(a_bound),
+        });
+    }
+    verdicts.extend(b_bounds.into_iter().flatten().map(BoundVerdict::OnlyInB));
+    Some(verdicts)
+}
+
+/// Returns the item a bound is on together with its self type, for bounds
+/// which can be related to other bounds on the same item.
+fn bound_key<'tcx>(clause: ty::Clause<'tcx>) -> Option<(DefId, Ty<'tcx>)> {
+    match clause.kind().skip_binder() {
+        ty::ClauseKind::Trait(pred) => Some((pred.def_id(), pred.self_ty())),
+        ty::ClauseKind::Projection(pred) => Some((pred.projection_term.def_id, pred.self_ty())),
+        _ => None,
+    }
+}
+
+/// Relates two bounds with the same [`bound_key`], treating bound variables
+/// up to renaming.
+fn relate_bounds<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    a: ty::Clause<'tcx>,
+    b: ty::Clause<'tcx>,
+) -> RelateResult<'tcx, ()> {
+    let a = tcx.anonymize_bound_vars(a.kind());
+    let b = tcx.anonymize_bound_vars(b.kind());
+    if a.bound_vars() != b.bound_vars() {
+        return Err(TypeError::Mismatch);
+    }
+
+    let mut relation = StructuralEq::new(tcx);
+    match (a.skip_binder(), b.skip_binder()) {
+        (ty::ClauseKind::Trait(a), ty::ClauseKind::Trait(b)) => {
+            relation.relate(a, b)?;
+        }
+        (ty::ClauseKind::Projection(a), ty::ClauseKind::Projection(b)) => {
+            relation.relate(a.projection_term, b.projection_term)?;
+            relation.relate(a.term, b.term)?;
+        }
+        (a, b) if a == b => {}
+        _ => return Err(TypeError::Mismatch),
+    }
+    Ok(())
+}
@@ -24,6 +24,7 @@
 use rustc_middle::traits::query::NoSolution;
 use rustc_middle::traits::solve::{CandidateSource, Certainty, Goal};
 use rustc_middle::traits::specialization_graph::OverlapMode;
+use rustc_middle::ty::error::TypeError;
 use rustc_middle::ty::fast_reject::{DeepRejectCtxt, TreatParams};
 use rustc_middle::ty::visit::{TypeSuperVisitable, TypeVisitable, TypeVisitableExt, TypeVisitor};
 use rustc_middle::ty::{self, Ty, TyCtxt};
@@ -198,6 +199,46 @@ fn fresh_impl_header_normalized<'tcx>(
     header
 }
 
+/// The result of unifying two impls, see [`unify_impls`].
+#[derive(Debug)]
+pub struct ImplUnification<'tcx> {
+    /// The header of the first impl, instantiated with the unifying args.
+    pub impl1_header: ty::ImplHeader<'tcx>,
+    /// The header of the second impl, instantiated with the unifying args.
+    pub impl2_header: ty::ImplHeader<'tcx>,
+    /// The obligations which have to hold for both impls to apply, i.e. the
+    /// obligations from equating their headers and the where-clauses of both.
+    pub obligations: PredicateObligations<'tcx>,
+}
+
+/// Instantiates the impls `impl1_def_id` and `impl2_def_id` with fresh inference
+/// variables and equates their [`ty::ImplSubject`]s.
+///
+/// On success, the unifying args are the `impl_args` of the returned headers,
+/// which still contain inference variables of `infcx`. Otherwise, returns why
+/// the impls do not unify. Note that this does not try to prove the returned
+/// obligations, so the impls may still not overlap.
+pub fn unify_impls<'tcx>(
+    infcx: &InferCtxt<'tcx>,
+    param_env: ty::ParamEnv<'tcx>,
+    impl1_def_id: DefId,
+    impl2_def_id: DefId,
+) -> Result<ImplUnification<'tcx>, TypeError<'tcx>> {
+    let impl1_header = fresh_impl_header_normalized(infcx, param_env, impl1_def_id);
+    let impl2_header = fresh_impl_header_normalized(infcx, param_env, impl2_def_id);
+
+    // Equate the headers to find their intersection (the general type, with infer vars,
+    // that may apply both impls).
+    let mut obligations = equate_impl_headers(infcx, param_env, &impl1_header, &impl2_header)?;
+    obligations.extend(
+        [&impl1_header.predicates, &impl2_header.predicates].into_iter().flatten().map(
+            |&predicate| Obligation::new(infcx.tcx, ObligationCause::dummy(), param_env, predicate),
+        ),
+    );
+
+    Ok(ImplUnification { impl1_header, impl2_header, obligations })
+}
+
 /// Can both impl `a` and impl `b` be satisfied by a common type (including
 /// where-clauses)? If so, returns an `ImplHeader` that unifies the two impls.
 #[instrument(level = "debug", skip(tcx))]
@@ -234,21 +275,10 @@ fn overlap<'tcx>(
     // empty environment.
     let param_env = ty::ParamEnv::empty();
 
-    let impl1_header = fresh_impl_header_normalized(selcx.infcx, param_env, impl1_def_id);
-    let impl2_header = fresh_impl_header_normalized(selcx.infcx, param_env, impl2_def_id);
-
-    // Equate the headers to find their intersection (the general type, with infer vars,
-    // that may apply both impls).
-    let mut obligations =
-        equate_impl_headers(selcx.infcx, param_env, &impl1_header, &impl2_header)?;
+    let ImplUnification { impl1_header, impl2_header: _, obligations } =
+        unify_impls(selcx.infcx, param_env, impl1_def_id, impl2_def_id).ok()?;
     debug!("overlap: unification check succeeded");
 
-    obligations.extend(
-        [&impl1_header.predicates, &impl2_header.predicates].into_iter().flatten().map(
-            |&predicate| Obligation::new(infcx.tcx, ObligationCause::dummy(), param_env, predicate),
-        ),
-    );
-
     let mut overflowing_predicates = Vec::new();
     if overlap_mode.use_implicit_negative() {
         match impl_intersection_has_impossible_obligation(selcx, &obligations) {
@@ -305,21 +335,23 @@ fn equate_impl_headers<'tcx>(
     param_env: ty::ParamEnv<'tcx>,
     impl1: &ty::ImplHeader<'tcx>,
     impl2: &ty::ImplHeader<'tcx>,
-) -> Option<PredicateObligations<'tcx>> {
-    let result =
-        match (impl1.trait_ref, impl2.trait_ref) {
-            (Some(impl1_ref), Some(impl2_ref)) => infcx
-                .at(&ObligationCause::dummy(), param_env)
-                .eq(DefineOpaqueTypes::Yes, impl1_ref, impl2_ref),
-            (None, None) => infcx.at(&ObligationCause::dummy(), param_env).eq(
-                DefineOpaqueTypes::Yes,
-                impl1.self_ty,
-                impl2.self_ty,
-            ),
-            _ => bug!("equate_impl_headers given mismatched impl kinds"),
-        };
+) -> Result<PredicateObligations<'tcx>, TypeError<'tcx>> {
+    // Impls of different polarity or constness still overlap, so relate both
+    // `ImplSubject`s with the polarity and constness of the first impl.
+    let tcx = infcx.tcx;
+    let polarity = tcx.impl_polarity(impl1.impl_def_id);
+    let constness = tcx.constness(impl1.impl_def_id);
+    let impl_subject = |header: &ty::ImplHeader<'tcx>| match header.trait_ref {
+        Some(trait_ref) => ty::ImplSubject::Trait(trait_ref, polarity, constness),
+        None => ty::ImplSubject::Inherent(header.self_ty),
+    };
 
-    result.map(|infer_ok| infer_ok.obligations).ok()
+    // A trait impl can never overlap with an inherent impl, relating them
+    // fails with `TypeError::ImplSubjectMismatch`.
+    infcx
+        .at(&ObligationCause::dummy(), param_env)
+        .eq(DefineOpaqueTypes::Yes, impl_subject(impl1), impl_subject(impl2))
+        .map(|infer_ok| infer_ok.obligations)
 }
 
 /// The result of [fn impl_intersection_has_impossible_obligation].
@@ -445,7 +477,7 @@ fn impl_intersection_has_negative_obligation(
 
     // Equate the headers to find their intersection (the general type, with infer vars,
     // that may apply both impls).
-    let Some(equate_obligations) =
+    let Ok(equate_obligations) =
         equate_impl_headers(infcx, param_env, &impl1_header, &impl2_header)
     else {
         return false;
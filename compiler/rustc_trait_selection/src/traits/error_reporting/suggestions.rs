@@ -3985,7 +3985,7 @@ fn look_for_iterator_item_mistakes<G: EmissionGuarantee>(
                 continue;
             };
             for diff in type_diffs {
-                let Sorts(expected_found) = diff else {
+                let Some(expected_found) = diff.sorts() else {
                     continue;
                 };
                 if tcx.is_diagnostic_item(sym::IteratorItem, *def_id)
@@ -4165,7 +4165,7 @@ fn point_at_chain<G: EmissionGuarantee>(
                     };
                     if primary_spans.is_empty()
                         || type_diffs.iter().any(|diff| {
-                            let Sorts(expected_found) = diff else {
+                            let Some(expected_found) = diff.sorts() else {
                                 return false;
                             };
                             self.can_eq(param_env, expected_found.found, ty)
@@ -4198,7 +4198,7 @@ fn point_at_chain<G: EmissionGuarantee>(
                         let assoc = with_forced_trimmed_paths!(self.tcx.def_path_str(assoc));
                         if !self.can_eq(param_env, ty, *prev_ty) {
                             if type_diffs.iter().any(|diff| {
-                                let Sorts(expected_found) = diff else {
+                                let Some(expected_found) = diff.sorts() else {
                                     return false;
                                 };
                                 self.can_eq(param_env, expected_found.found, ty)
@@ -4248,7 +4248,7 @@ fn probe_assoc_types_at_expr(
         let ocx = ObligationCtxt::new(self.infcx);
         let mut assocs_in_this_method = Vec::with_capacity(type_diffs.len());
         for diff in type_diffs {
-            let Sorts(expected_found) = diff else {
+            let Some(expected_found) = diff.sorts() else {
                 continue;
             };
             let ty::Alias(ty::Projection, proj) = expected_found.expected.kind() else {
@@ -2008,8 +2008,10 @@ fn report_similar_impl_candidates(
                         StringPart::normal("`"),
                     ]);
 
-                    if let [TypeError::Sorts(exp_found)] = &terrs[..] {
-                        let exp_found = self.resolve_vars_if_possible(*exp_found);
+                    if let [terr] = &terrs[..]
+                        && let Some(exp_found) = terr.sorts()
+                    {
+                        let exp_found = self.resolve_vars_if_possible(exp_found);
                         err.help(format!(
                             "for that trait implementation, expected `{}`, found `{}`",
                             exp_found.expected, exp_found.found
@@ -3,6 +3,7 @@
 //! [rustc dev guide]: https://rustc-dev-guide.rust-lang.org/traits/resolution.html
 
 pub mod auto_trait;
+mod bound_comparison;
 pub(crate) mod coherence;
 pub mod const_evaluatable;
 mod engine;
@@ -34,6 +35,7 @@
 use rustc_middle::span_bug;
 use rustc_middle::ty::error::{ExpectedFound, TypeError};
 use rustc_middle::ty::fold::TypeFoldable;
+use rustc_middle::ty::relate::{diff_tys, TypeDiff};
 use rustc_middle::ty::visit::{TypeVisitable, TypeVisitableExt};
 use rustc_middle::ty::{self, Ty, TyCtxt, TypeFolder, TypeSuperVisitable, Upcast};
 use rustc_middle::ty::{GenericArgs, GenericArgsRef};
@@ -43,7 +45,9 @@
 use std::fmt::Debug;
 use std::ops::ControlFlow;
 
+pub use self::bound_comparison::{compare_trait_bounds, BoundVerdict};
 pub use self::coherence::{add_placeholder_note, orphan_check_trait_ref, overlapping_impls};
+pub use self::coherence::{unify_impls, ImplUnification};
 pub use self::coherence::{InCrate, IsFirstInputType, UncoveredTyParams};
 pub use self::coherence::{OrphanCheckErr, OrphanCheckMode, OverlapResult};
 pub use self::engine::{ObligationCtxt, TraitEngineExt};
@@ -59,7 +63,8 @@
 pub use self::specialize::specialization_graph::FutureCompatOverlapError;
 pub use self::specialize::specialization_graph::FutureCompatOverlapErrorKind;
 pub use self::specialize::{
-    specialization_graph, translate_args, translate_args_with_cause, OverlapError,
+    at_least_as_specialized, specialization_graph, translate_args, translate_args_with_cause,
+    OverlapError, SpecializationFailure,
 };
 pub use self::structural_match::search_for_structural_match_violation;
 pub use self::structural_normalize::StructurallyNormalizeExt;
@@ -100,6 +105,43 @@ pub fn is_true_error(&self) -> bool {
             }
         }
     }
+
+    /// Returns the error of the type relation which caused the obligation to
+    /// fail, if it failed because two types or constants didn't relate.
+    pub fn type_error(&self) -> Option<TypeError<'tcx>> {
+        match self.code {
+            FulfillmentErrorCode::Select(SelectionError::SignatureMismatch(ref data)) => {
+                Some(data.terr)
+            }
+            FulfillmentErrorCode::Project(ref err) => Some(err.err),
+            FulfillmentErrorCode::Subtype(_, err) | FulfillmentErrorCode::ConstEquate(_, err) => {
+                Some(err)
+            }
+            FulfillmentErrorCode::Select(_)
+            | FulfillmentErrorCode::Cycle(_)
+            | FulfillmentErrorCode::Ambiguity { overflow: _ } => None,
+        }
+    }
+
+    /// Returns where exactly the two types which didn't relate differ, see
+    /// [`diff_tys`], if the obligation failed because of two mismatched types.
+    pub fn type_diff(&self, tcx: TyCtxt<'tcx>) -> Option<TypeDiff<'tcx>> {
+        let (expected, found) = match (&self.code, self.type_error()?) {
+            (FulfillmentErrorCode::Subtype(expected_found, _), _) => {
+                (expected_found.expected, expected_found.found)
+            }
+            (
+                _,
+                TypeError::Sorts(expected_found)
+                | TypeError::ArgumentSorts(expected_found, _)
+                | TypeError::TupleElementSorts(expected_found, _),
+            ) => {
+                (expected_found.expected, expected_found.found)
+            }
+            _ => return None,
+        };
+        diff_tys(tcx, expected, found)
+    }
 }
 
 impl<'tcx> Debug for FulfillmentError<'tcx> {
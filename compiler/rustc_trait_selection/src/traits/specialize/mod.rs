@@ -18,12 +18,14 @@
 use crate::infer::{InferCtxt, InferOk, TyCtxtInferExt};
 use crate::traits::select::IntercrateAmbiguityCause;
 use crate::traits::{
-    self, coherence, FutureCompatOverlapErrorKind, ObligationCause, ObligationCtxt,
+    self, coherence, FulfillmentError, FutureCompatOverlapErrorKind, ObligationCause,
+    ObligationCtxt,
 };
 use rustc_data_structures::fx::FxIndexSet;
 use rustc_errors::{codes::*, Diag, EmissionGuarantee};
 use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_middle::bug;
+use rustc_middle::ty::error::TypeError;
 use rustc_middle::ty::{self, ImplSubject, Ty, TyCtxt, TypeVisitableExt};
 use rustc_middle::ty::{GenericArgs, GenericArgsRef};
 use rustc_session::lint::builtin::COHERENCE_LEAK_CHECK;
@@ -44,6 +46,23 @@ pub struct OverlapError<'tcx> {
     pub overflowing_predicates: Vec<ty::Predicate<'tcx>>,
 }
 
+/// Why an impl is not at least as specialized as another one, see
+/// [`at_least_as_specialized`].
+#[derive(Debug)]
+pub enum SpecializationFailure<'tcx> {
+    /// One of the impls is an inherent impl, which can't take part in
+    /// specialization.
+    InherentImpl(DefId),
+    /// The impls have a different polarity, e.g. a negative impl can never
+    /// specialize a positive one.
+    PolarityMismatch,
+    /// The trait refs of the impls do not unify.
+    TraitRefMismatch(TypeError<'tcx>),
+    /// The where clauses of the more general impl do not hold given those of
+    /// the more specialized one.
+    Unfulfilled(Vec<FulfillmentError<'tcx>>),
+}
+
 /// Given the generic parameters for the requested impl, translate it to the generic parameters
 /// appropriate for the actual item definition (whether it be in that impl,
 /// a parent impl, or the trait).
@@ -122,7 +141,7 @@ pub fn translate_args_with_cause<'tcx>(
             }
 
             fulfill_implication(infcx, param_env, source_trait_ref, source_impl, target_impl, cause)
-                .unwrap_or_else(|()| {
+                .unwrap_or_else(|_| {
                     bug!(
                         "When translating generic parameters from {source_impl:?} to \
                         {target_impl:?}, the expected specialization failed to hold"
@@ -171,7 +190,28 @@ pub(super) fn specializes(tcx: TyCtxt<'_>, (impl1_def_id, impl2_def_id): (DefId,
         }
     }
 
-    let impl1_trait_header = tcx.impl_trait_header(impl1_def_id).unwrap();
+    at_least_as_specialized(tcx, impl1_def_id, impl2_def_id).is_ok()
+}
+
+/// Checks whether `impl1` applies to a subset of the types `impl2` applies to,
+/// ignoring whether specialization is enabled.
+///
+/// On success, returns the generic args of `impl2` expressed in terms of the
+/// generic parameters of `impl1`, with regions erased, one per parameter of
+/// `impl2`. Args which aren't fully determined by `impl1` are `None`.
+/// Otherwise, returns why `impl1` is not at least as specialized as `impl2`.
+#[instrument(skip(tcx), level = "debug")]
+pub fn at_least_as_specialized<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    impl1_def_id: DefId,
+    impl2_def_id: DefId,
+) -> Result<Vec<Option<ty::GenericArg<'tcx>>>, SpecializationFailure<'tcx>> {
+    let Some(impl1_trait_header) = tcx.impl_trait_header(impl1_def_id) else {
+        return Err(SpecializationFailure::InherentImpl(impl1_def_id));
+    };
+    if tcx.impl_trait_header(impl2_def_id).is_none() {
+        return Err(SpecializationFailure::InherentImpl(impl2_def_id));
+    }
 
     // We determine whether there's a subset relationship by:
     //
@@ -187,7 +227,7 @@ pub(super) fn specializes(tcx: TyCtxt<'_>, (impl1_def_id, impl2_def_id): (DefId,
 
     // Currently we do not allow e.g., a negative impl to specialize a positive one
     if impl1_trait_header.polarity != tcx.impl_polarity(impl2_def_id) {
-        return false;
+        return Err(SpecializationFailure::PolarityMismatch);
     }
 
     // create a parameter environment corresponding to a (placeholder) instantiation of impl1
@@ -197,15 +237,24 @@ pub(super) fn specializes(tcx: TyCtxt<'_>, (impl1_def_id, impl2_def_id): (DefId,
     let infcx = tcx.infer_ctxt().build();
 
     // Attempt to prove that impl2 applies, given all of the above.
-    fulfill_implication(
+    let impl2_args = fulfill_implication(
         &infcx,
         penv,
         impl1_trait_header.trait_ref.instantiate_identity(),
         impl1_def_id,
         impl2_def_id,
         |_, _| ObligationCause::dummy(),
-    )
-    .is_ok()
+    )?;
+    // Region resolution doesn't run here, so the region variables in the
+    // args are meaningless outside of this inference context. No other
+    // inference variables may leave it either.
+    Ok(impl2_args
+        .iter()
+        .map(|arg| {
+            let arg = tcx.erase_regions(arg);
+            if arg.has_non_region_infer() { None } else { Some(arg) }
+        })
+        .collect())
 }
 
 /// Attempt to fulfill all obligations of `target_impl` after unification with
@@ -220,7 +269,7 @@ fn fulfill_implication<'tcx>(
     source_impl: DefId,
     target_impl: DefId,
     error_cause: impl Fn(usize, Span) -> ObligationCause<'tcx>,
-) -> Result<GenericArgsRef<'tcx>, ()> {
+) -> Result<GenericArgsRef<'tcx>, SpecializationFailure<'tcx>> {
     debug!(
         "fulfill_implication({:?}, trait_ref={:?} |- {:?} applies)",
         param_env, source_trait_ref, target_impl
@@ -239,26 +288,32 @@ fn fulfill_implication<'tcx>(
             }
         };
 
-    let source_trait = ImplSubject::Trait(source_trait_ref);
-
     let selcx = SelectionContext::new(infcx);
     let target_args = infcx.fresh_args_for_item(DUMMY_SP, target_impl);
-    let (target_trait, obligations) =
+    let (target_subject, obligations) =
         util::impl_subject_and_oblig(&selcx, param_env, target_impl, target_args, error_cause);
+    // A const impl may be specialized by a non-const one and vice versa, which
+    // is checked elsewhere, so only equate the trait refs of the impls.
+    let ImplSubject::Trait(target_trait, ..) = target_subject else {
+        bug!("inherent impl {target_impl:?} cannot be specialized");
+    };
 
     // do the impls unify? If not, no specialization.
-    let Ok(InferOk { obligations: more_obligations, .. }) = infcx
+    let InferOk { obligations: more_obligations, .. } = infcx
         .at(&ObligationCause::dummy(), param_env)
         // Ok to use `Yes`, as all the generic params are already replaced by inference variables,
         // which will match the opaque type no matter if it is defining or not.
         // Any concrete type that would match the opaque would already be handled by coherence rules,
         // and thus either be ok to match here and already have errored, or it won't match, in which
         // case there is no issue anyway.
-        .eq(DefineOpaqueTypes::Yes, source_trait, target_trait)
-    else {
-        debug!("fulfill_implication: {:?} does not unify with {:?}", source_trait, target_trait);
-        return Err(());
-    };
+        .eq(DefineOpaqueTypes::Yes, source_trait_ref, target_trait)
+        .map_err(|err| {
+            debug!(
+                "fulfill_implication: {:?} does not unify with {:?}",
+                source_trait_ref, target_trait
+            );
+            SpecializationFailure::TraitRefMismatch(err)
+        })?;
 
     // Needs to be `in_snapshot` because this function is used to rebase
     // generic parameters, which may happen inside of a select within a probe.
@@ -273,15 +328,18 @@ fn fulfill_implication<'tcx>(
         debug!(
             "fulfill_implication: for impls on {:?} and {:?}, \
                  could not fulfill: {:?} given {:?}",
-            source_trait,
+            source_trait_ref,
             target_trait,
             errors,
             param_env.caller_bounds()
         );
-        return Err(());
+        return Err(SpecializationFailure::Unfulfilled(errors));
     }
 
-    debug!("fulfill_implication: an impl for {:?} specializes {:?}", source_trait, target_trait);
+    debug!(
+        "fulfill_implication: an impl for {:?} specializes {:?}",
+        source_trait_ref, target_trait
+    );
 
     // Now resolve the *generic parameters* we built for the target earlier, replacing
     // the inference variables inside with whatever we got from fulfillment.
@@ -54,4 +54,5 @@ fn visit_with<F: $crate::visit::TypeVisitor<I>>(
     rustc_ast_ir::Mutability,
     rustc_ast_ir::Movability,
     crate::PredicatePolarity,
+    crate::ImplPolarity,
 }
@@ -2080,7 +2080,7 @@ fn get_id_for_impl<'tcx>(tcx: TyCtxt<'tcx>, impl_id: ItemId) -> String {
         }
         ItemId::Blanket { impl_id, .. } | ItemId::DefId(impl_id) => {
             match tcx.impl_subject(impl_id).skip_binder() {
-                ty::ImplSubject::Trait(trait_ref) => {
+                ty::ImplSubject::Trait(trait_ref, ..) => {
                     (trait_ref.args[0].expect_ty(), Some(trait_ref))
                 }
                 ty::ImplSubject::Inherent(ty) => (ty, None),
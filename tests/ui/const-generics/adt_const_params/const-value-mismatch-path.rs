@@ -0,0 +1,23 @@
+// Check that mismatched const arguments point out the field or element in
+// which they differ.
+
+#![feature(adt_const_params)]
+#![allow(incomplete_features)]
+
+use std::marker::ConstParamTy;
+
+#[derive(PartialEq, Eq, ConstParamTy)]
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+struct Foo<const P: Point>;
+struct Bar<const A: [u32; 3]>;
+
+fn main() {
+    let _: Foo<{ Point { x: 1, y: 2 } }> = Foo::<{ Point { x: 1, y: 3 } }>;
+    //~^ ERROR mismatched types
+    let _: Bar<{ [1, 2, 3] }> = Bar::<{ [1, 5, 3] }>;
+    //~^ ERROR mismatched types
+}
@@ -0,0 +1,18 @@
+// Inline consts in types inherit the generics of their enclosing item, so two
+// of them can only differ in the parent's args under `generic_const_exprs`.
+//
+// FIXME(generic_const_exprs): const blocks aren't supported in generic
+// constants yet, so this is rejected before the array types are related. Once
+// they are, this should report that `N` and `M` differ for the generic
+// parameter `N` of `foo`, instead of in terms of the inline const's own args.
+
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+fn foo<const N: usize, const M: usize>() {
+    let _: [u8; const { N }] = [0u8; const { M }];
+    //~^ ERROR overly complex generic constant
+    //~| ERROR overly complex generic constant
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+//@ check-pass
+// Const expressions which are equal modulo commutativity and constant folding
+// unify with each other.
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+fn commuted<const N: usize>() -> [u8; 1 + N]
+where
+    [(); N + 1]:,
+{
+    [0; N + 1]
+}
+
+fn folded<const N: usize>() -> [u8; N + 3]
+where
+    [(); N + 1 + 2]:,
+{
+    [0; N + 1 + 2]
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+// The validator reports why the type of a field projection doesn't match the field.
+//
+//@ build-fail
+//@ edition: 2021
+//@ compile-flags: --crate-type=lib
+//@ failure-status: 101
+//@ dont-check-compiler-stderr
+//@ error-pattern: but actual type is `u32`: expected `u64`, found `u32`
+#![feature(custom_mir, core_intrinsics)]
+use core::intrinsics::mir::*;
+
+#[custom_mir(dialect = "runtime", phase = "optimized")]
+pub fn f(opt: Option<u32>) -> u64 {
+    mir! {
+        {
+            RET = Field::<u64>(Variant(opt, 1), 0);
+            Return()
+        }
+    }
+}
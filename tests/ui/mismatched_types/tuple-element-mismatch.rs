@@ -0,0 +1,8 @@
+// Check that a mismatch of a single element of a tuple points at that element
+// of the expected type.
+
+fn main() {
+    let t = (1i32, "a", 2.0f64);
+    let _x: (i32, String, f64) = t;
+    //~^ ERROR mismatched types
+}
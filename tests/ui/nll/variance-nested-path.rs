@@ -0,0 +1,10 @@
+// Check that the variance notes point out the types which the lifetime
+// leading to the error is nested in.
+
+fn compare<'a, 'b>(x: *const &mut Vec<Vec<&'a i32>>, y: *const &mut Vec<Vec<&'b i32>>) {
+    x == y;
+    //~^ ERROR lifetime may not live long enough
+    //~| ERROR lifetime may not live long enough
+}
+
+fn main() {}
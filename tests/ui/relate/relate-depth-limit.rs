@@ -0,0 +1,15 @@
+// Tests that relating types nested deeper than the relate depth limit fails
+// gracefully instead of overflowing the stack.
+
+//@ compile-flags: -Zrelate-depth-limit=4
+
+#![feature(rustc_attrs)]
+#![crate_type = "lib"]
+
+#[rustc_relate_test(eq)]
+pub type WithinLimit = ((((u8,),),), (((i8,),),));
+//~^ ERROR eq((((u8,),),), (((i8,),),)) = Err(Sorts(ExpectedFound { expected: u8, found: i8 }))
+
+#[rustc_relate_test(eq)]
+pub type BeyondLimit = ((((((u8,),),),),), (((((i8,),),),),));
+//~^ ERROR = Err(RelateDepthLimitReached(4))
@@ -0,0 +1,28 @@
+// Tests computing where exactly two types differ.
+
+#![feature(rustc_attrs)]
+#![crate_type = "lib"]
+
+#[rustc_relate_test(diff)]
+pub type Equal = ((u8, u16), (u8, u16));
+//~^ ERROR diff((u8, u16), (u8, u16)) = None
+
+#[rustc_relate_test(diff)]
+pub type Outermost = (u8, bool);
+//~^ ERROR diff(u8, bool) = Some([[]: `u8` != `bool`])
+
+#[rustc_relate_test(diff)]
+pub type Nested = (Vec<(u32, u8)>, Vec<(u32, i8)>);
+//~^ ERROR diff(Vec<(u32, u8)>, Vec<(u32, i8)>) = Some([[0, 1]: `u8` != `i8`])
+
+#[rustc_relate_test(diff)]
+pub type Siblings = ((u8, u16, u32), (i8, u16, i32));
+//~^ ERROR diff((u8, u16, u32), (i8, u16, i32)) = Some([[0]: `u8` != `i8`, [2]: `u32` != `i32`])
+
+#[rustc_relate_test(diff)]
+pub type FnOutput = (fn(u8) -> u16, fn(u8) -> u32);
+//~^ ERROR diff(fn(u8) -> u16, fn(u8) -> u32) = Some([[1]: `u16` != `u32`])
+
+#[rustc_relate_test(diff)]
+pub type Pointee = (&'static u8, &'static i8);
+//~^ ERROR diff(&'static u8, &'static i8) = Some([[1]: `u8` != `i8`])
@@ -0,0 +1,24 @@
+// Tests matching a type against a pattern whose parameters are holes.
+
+#![feature(rustc_attrs)]
+#![crate_type = "lib"]
+
+#[rustc_relate_test(matches)]
+pub type Holes<T> = (Vec<Option<T>>, Vec<Option<u32>>);
+//~^ ERROR matches(Vec<Option<T>>, Vec<Option<u32>>) = Ok([T => u32])
+
+#[rustc_relate_test(matches)]
+pub type Repeated<T> = ((T, T), (u8, u16));
+//~^ ERROR matches((T, T), (u8, u16)) = Err(Sorts(ExpectedFound { expected: T, found: u16 }))
+
+#[rustc_relate_test(matches)]
+pub type Regions<'a, T> = (&'a T, &'static str);
+//~^ ERROR matches(&'a T, &'static str) = Ok(['a => 'static, T => str])
+
+#[rustc_relate_test(matches)]
+pub type Shape<T> = (Vec<T>, Option<u8>);
+//~^ ERROR matches(Vec<T>, Option<u8>) = Err(Sorts(
+
+#[rustc_relate_test(matches)]
+pub type Escaping<T> = (fn(T), for<'a> fn(&'a u8));
+//~^ ERROR matches(fn(T), for<'a> fn(&'a u8)) = Err(Sorts(
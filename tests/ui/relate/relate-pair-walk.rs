@@ -0,0 +1,24 @@
+// Tests walking the corresponding components of two types in lockstep.
+
+#![feature(rustc_attrs)]
+#![crate_type = "lib"]
+
+#[rustc_relate_test(walk)]
+pub type Equal = ((u8, u16), (u8, u16));
+//~^ ERROR walk((u8, u16), (u8, u16)) = [[] + `(u8, u16)` ~ `(u8, u16)`]
+
+#[rustc_relate_test(walk)]
+pub type Nested = (Vec<(u32, u8)>, Vec<(u32, i8)>);
+//~^ ERROR walk(Vec<(u32, u8)>, Vec<(u32, i8)>) = [[] + `Vec<(u32, u8)>` ~ `Vec<(u32, i8)>`, [0] +
+
+#[rustc_relate_test(walk)]
+pub type FnInput = (fn(u8) -> u16, fn(i8) -> u16);
+//~^ ERROR walk(fn(u8) -> u16, fn(i8) -> u16) = [[] + `fn(u8) -> u16` ~ `fn(i8) -> u16`, [0] -
+
+#[rustc_relate_test(walk)]
+pub type MutRef = (&'static mut u8, &'static mut u16);
+//~^ ERROR [[] + `&'static mut u8` ~ `&'static mut u16`, [0] + `'static` ~ `'static`, [1] o
+
+#[rustc_relate_test(walk)]
+pub type TupleSize = ((u8, u16), (u8, u16, u32));
+//~^ ERROR walk((u8, u16), (u8, u16, u32)) = [[] + `(u8, u16)` ~ `(u8, u16, u32)`]
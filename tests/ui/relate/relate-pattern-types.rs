@@ -0,0 +1,21 @@
+// Tests that pattern types relate if their patterns include the same values.
+
+#![feature(pattern_types, rustc_attrs)]
+#![feature(core_pattern_type)]
+#![feature(core_pattern_types)]
+#![allow(incomplete_features)]
+#![crate_type = "lib"]
+
+use std::pat::pattern_type;
+
+#[rustc_relate_test(eq)]
+pub type FullRange = (pattern_type!(u8 is 0..=255), pattern_type!(u8 is 0..));
+//~^ ERROR eq((u8) is 0..=255, (u8) is 0..=) = Ok(())
+
+#[rustc_relate_test(eq)]
+pub type ExclusiveEnd = (pattern_type!(u32 is 0..5), pattern_type!(u32 is 0..=4));
+//~^ ERROR eq((u32) is 0..5, (u32) is 0..=4) = Ok(())
+
+#[rustc_relate_test(eq)]
+pub type Different = (pattern_type!(u32 is 1..), pattern_type!(u32 is 0..));
+//~^ ERROR = Err(PatternMismatch(ExpectedFound { expected: 1..=, found: 0..= }))
@@ -0,0 +1,41 @@
+// Tests relating where clauses with `#[rustc_relate_test]`. Relating
+// predicates of a different kind reports the kinds of both of them.
+
+#![feature(rustc_attrs)]
+#![crate_type = "lib"]
+
+#[rustc_relate_test(eq)]
+pub fn same_kind()
+//~^ ERROR eq(u8: Copy, u16: Copy) = Err(expected `u8`, found `u16`)
+where
+    u8: Copy,
+    u16: Copy,
+{
+}
+
+#[rustc_relate_test(eq, matches)]
+pub fn kind_mismatch()
+//~^ ERROR eq(u8: Copy, (): 'static) = Err(expected a trait predicate, found a type outlives
+//~| ERROR matches(u8: Copy, (): 'static) = Err(expected a trait predicate, found a type outlives
+where
+    u8: Copy,
+    (): 'static,
+{
+}
+
+#[rustc_relate_test(sub)]
+//~^ ERROR relation `sub` can't relate where clauses, expected `eq` or `matches`
+pub fn unsupported()
+where
+    u8: Copy,
+    u16: Copy,
+{
+}
+
+#[rustc_relate_test(eq)]
+pub fn one_where_clause()
+//~^ ERROR can only be applied to type aliases of pairs of types and functions with two where
+where
+    u8: Copy,
+{
+}
@@ -0,0 +1,30 @@
+// Tests scoring how similar two types are, where lower scores are closer.
+
+#![feature(rustc_attrs)]
+#![crate_type = "lib"]
+
+use std::rc::Rc;
+
+#[rustc_relate_test(similarity)]
+pub type Equal = (u32, u32);
+//~^ ERROR similarity(u32, u32) = 0
+
+#[rustc_relate_test(similarity)]
+pub type Numeric = (u32, i64);
+//~^ ERROR similarity(u32, i64) = 4
+
+#[rustc_relate_test(similarity)]
+pub type Wrapped = (Rc<String>, String);
+//~^ ERROR similarity(Rc<String>, String) = 4
+
+#[rustc_relate_test(similarity)]
+pub type Mutability = (&'static mut u8, &'static u16);
+//~^ ERROR similarity(&'static mut u8, &'static u16) = 6
+
+#[rustc_relate_test(similarity)]
+pub type Nested = (Vec<(u8, &'static str)>, Vec<(u16, String)>);
+//~^ ERROR similarity(Vec<(u8, &'static str)>, Vec<(u16, String)>) = 20
+
+#[rustc_relate_test(similarity)]
+pub type DifferentAdts = (Vec<u8>, Option<u8>);
+//~^ ERROR similarity(Vec<u8>, Option<u8>) = 16
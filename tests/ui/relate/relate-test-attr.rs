@@ -0,0 +1,34 @@
+// Tests the `#[rustc_relate_test]` attribute, which dumps the result of
+// relating the two types of a pair.
+
+#![feature(rustc_attrs)]
+#![crate_type = "lib"]
+
+#[rustc_relate_test(eq)]
+pub type Equal = (u32, u32);
+//~^ ERROR eq(u32, u32) = Ok(())
+
+#[rustc_relate_test(eq)]
+pub type Mismatch = (u32, i32);
+//~^ ERROR eq(u32, i32) = Err(Sorts(ExpectedFound { expected: u32, found: i32 }))
+
+#[rustc_relate_test(sub)]
+pub type TupleSize = ((u8, u8), (u8,));
+//~^ ERROR sub((u8, u8), (u8,)) = Err(TupleSize(ExpectedFound { expected: 2, found: 1 }))
+
+#[rustc_relate_test(eq)]
+pub type Mutability = (&'static u8, &'static mut u8);
+//~^ ERROR eq(&'static u8, &'static mut u8) = Err(Mutability)
+
+#[rustc_relate_test(lub, glb)]
+pub type Bounds = (u32, u32);
+//~^ ERROR lub(u32, u32) = Ok(u32)
+//~| ERROR glb(u32, u32) = Ok(u32)
+
+#[rustc_relate_test(eq)]
+pub type NotAPair = u32;
+//~^ ERROR `#[rustc_relate_test]` can only be applied to type aliases of pairs of types
+
+#[rustc_relate_test(unify)]
+//~^ ERROR unknown relation `unify`
+pub type UnknownKind = (u32, u32);
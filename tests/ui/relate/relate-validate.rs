@@ -0,0 +1,28 @@
+// Tests that subtyping, equating, and computing least upper bounds pass the
+// checks of `-Zvalidate-relate`, which only run with debug assertions.
+
+//@ check-pass
+//@ compile-flags: -Zvalidate-relate
+
+fn foo<'a>(x: &'a u32) -> &'a u32 {
+    x
+}
+
+fn bar<'a>(x: &'a u32) -> &'a u32 {
+    x
+}
+
+fn main() {
+    let x = 3;
+    let r: &u32 = &x;
+    let s: &'static str = "static";
+    let _: &str = s;
+
+    // Computes the least upper bound of two function items.
+    let f = if r == &3 { foo } else { bar };
+    let _: for<'a> fn(&'a u32) -> &'a u32 = f;
+
+    let mut v = Vec::new();
+    v.push((r, s));
+    let _: Vec<(&u32, &str)> = v;
+}
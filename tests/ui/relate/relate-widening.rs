@@ -0,0 +1,36 @@
+// Tests relating types up to lossless numeric widening.
+
+#![feature(rustc_attrs)]
+#![crate_type = "lib"]
+
+#[rustc_relate_test(widen)]
+pub type Integers = (u8, u32);
+//~^ ERROR widen(u8, u32) = Ok(u32, [u8 -> u32])
+
+#[rustc_relate_test(widen)]
+pub type Narrowing = (u32, u8);
+//~^ ERROR widen(u32, u8) = Err(Sorts(ExpectedFound { expected: u32, found: u8 }))
+
+#[rustc_relate_test(widen)]
+pub type SignedToUnsigned = (i8, u16);
+//~^ ERROR widen(i8, u16) = Err(Sorts(ExpectedFound { expected: i8, found: u16 }))
+
+#[rustc_relate_test(widen)]
+pub type TargetDependent = (u8, usize);
+//~^ ERROR widen(u8, usize) = Err(Sorts(ExpectedFound { expected: u8, found: usize }))
+
+#[rustc_relate_test(widen)]
+pub type Nested = ((u8, f32), (i16, f64));
+//~^ ERROR widen((u8, f32), (i16, f64)) = Ok((i16, f64), [u8 -> i16, f32 -> f64])
+
+#[rustc_relate_test(widen)]
+pub type IntToFloat = (i32, f64);
+//~^ ERROR widen(i32, f64) = Ok(f64, [i32 -> f64])
+
+#[rustc_relate_test(widen)]
+pub type FnInput = (fn(u32), fn(u8));
+//~^ ERROR widen(fn(u32), fn(u8)) = Ok(fn(u8), [u8 -> u32])
+
+#[rustc_relate_test(widen)]
+pub type Invariant = (*mut u8, *mut u16);
+//~^ ERROR widen(*mut u8, *mut u16) = Err(Sorts(ExpectedFound { expected: u8, found: u16 }))
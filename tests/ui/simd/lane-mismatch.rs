@@ -0,0 +1,20 @@
+// Mismatched uses of a SIMD vector say whether their lane counts or their
+// element types differ.
+
+#![feature(repr_simd)]
+#![allow(dead_code)]
+
+#[repr(simd)]
+struct Simd<T, const N: usize>([T; N]);
+
+fn four(_x: Simd<f32, 4>) {}
+
+fn lanes(x: Simd<f32, 8>) {
+    four(x); //~ ERROR mismatched types
+}
+
+fn elements(x: Simd<i32, 4>) {
+    four(x); //~ ERROR mismatched types
+}
+
+fn main() {}
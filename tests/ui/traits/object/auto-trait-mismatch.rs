@@ -0,0 +1,16 @@
+// Mismatched trait objects which only differ in their auto traits name the
+// auto traits which are missing.
+
+trait Foo {}
+
+fn send(_x: Box<dyn Foo + Send>) {}
+
+fn different(x: Box<dyn Foo + Sync>) {
+    send(x); //~ ERROR mismatched types
+}
+
+fn missing(x: Box<dyn Foo>) {
+    send(x); //~ ERROR mismatched types
+}
+
+fn main() {}